@@ -0,0 +1,301 @@
+//! Subcommands working with saved histograms.
+//!
+//! Histograms are read from files in the histr format, or from UHI JSON if
+//! the file has a `.json` extension.
+
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use histr::axis::{Axis, FixedWidthAxis};
+use histr::stats::{chi2_test, ks_test, moments};
+use histr::uhi::from_uhi_json;
+use histr::H1;
+
+use crate::output::{write_output, write_rows};
+use crate::OutputArgs;
+
+#[derive(Debug, Args)]
+pub struct MergeArgs {
+    /// Histogram files.
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Save the sum to a file in the histr format.
+    #[arg(short = 'o', long, value_name = "FILE")]
+    save: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct InfoArgs {
+    /// Histogram files.
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    first: PathBuf,
+    second: PathBuf,
+}
+
+fn load(path: &Path) -> Result<H1<'static>, Box<dyn Error>> {
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    let h1 = if is_json {
+        fs::read_to_string(path)
+            .map_err(Box::from)
+            .and_then(|json| from_uhi_json(&json))
+    } else {
+        H1::load(path)
+    };
+    h1.map_err(|error| format!("{}: {error}", path.display()).into())
+}
+
+fn describe_axis(axis: &dyn Axis) -> String {
+    let kind = if axis.as_any().is::<FixedWidthAxis>() {
+        "fixed-width"
+    } else {
+        "general"
+    };
+    format!(
+        "{kind} axis with {} bins in [{}, {}]",
+        axis.len(),
+        axis.min_edge(),
+        axis.max_edge()
+    )
+}
+
+// Same type of axis with exactly the same edges.
+fn check_axes(
+    path: &Path,
+    axis: &dyn Axis,
+    reference_path: &Path,
+    reference: &dyn Axis,
+) -> Result<(), String> {
+    if axis.as_any().type_id() == reference.as_any().type_id() && axis.equal_bins(reference) {
+        return Ok(());
+    }
+    let (description, reference_description) = (describe_axis(axis), describe_axis(reference));
+    let details = if description == reference_description {
+        "different bin edges".to_string()
+    } else {
+        format!("{description} instead of {reference_description}")
+    };
+    Err(format!(
+        "{}: axis differs from {} ({details}).",
+        path.display(),
+        reference_path.display()
+    ))
+}
+
+// Number with four significant decimals.
+fn format_number(value: f64) -> String {
+    if value != 0.0 && value.abs() < 1e-3 {
+        format!("{value:.3e}")
+    } else {
+        format!("{value:.4}")
+    }
+}
+
+pub fn merge(args: &MergeArgs, output: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let (first_path, paths) = args
+        .files
+        .split_first()
+        .ok_or("At least one file is required.")?;
+    let mut sum = load(first_path)?;
+    for path in paths {
+        let h1 = load(path)?;
+        check_axes(path, h1.axis(), first_path, sum.axis())?;
+        sum = (&sum + &h1)?;
+    }
+    if let Some(path) = &args.save {
+        sum.save(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+    }
+    write_output(&sum, &args.output.options(), output)
+}
+
+pub fn info(args: &InfoArgs, output: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    for (i, path) in args.files.iter().enumerate() {
+        let h1 = load(path)?;
+        let moments = moments(&h1);
+        if i > 0 {
+            writeln!(output)?;
+        }
+        writeln!(output, "{}", path.display())?;
+        writeln!(output, "  axis:      {}", describe_axis(h1.axis()))?;
+        writeln!(output, "  total:     {}", h1.total())?;
+        writeln!(output, "  underflow: {}", h1.underflow().value)?;
+        writeln!(output, "  overflow:  {}", h1.overflow().value)?;
+        writeln!(output, "  mean:      {}", moments.mean)?;
+        writeln!(output, "  std dev:   {}", moments.std_dev)?;
+        writeln!(output, "  skewness:  {}", moments.skewness)?;
+        writeln!(output, "  kurtosis:  {}", moments.kurtosis)?;
+        for (key, value) in h1.metadata() {
+            writeln!(output, "  {key}: {value}")?;
+        }
+    }
+    Ok(())
+}
+
+pub fn diff(args: &DiffArgs, output: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let first = load(&args.first)?;
+    let second = load(&args.second)?;
+    check_axes(&args.second, second.axis(), &args.first, first.axis())?;
+
+    let edges = first.axis().bin_edges();
+    let (first_values, second_values) = (first.values(), second.values());
+    let mut rows = vec![["bin", "lower", "upper", "first", "second", "difference"]
+        .map(String::from)
+        .to_vec()];
+    for (i, (a, b)) in first_values.iter().zip(second_values.iter()).enumerate() {
+        rows.push(vec![
+            i.to_string(),
+            edges[i].to_string(),
+            edges[i + 1].to_string(),
+            a.to_string(),
+            b.to_string(),
+            (b - a).to_string(),
+        ]);
+    }
+    write_rows(&rows, output)?;
+
+    writeln!(output)?;
+    match chi2_test(&first, &second) {
+        Ok(result) => writeln!(
+            output,
+            "chi2/ndf: {}/{}, p-value: {}",
+            format_number(result.statistic),
+            result.ndf,
+            format_number(result.p_value)
+        )?,
+        Err(error) => writeln!(output, "chi2: {error}")?,
+    }
+    match ks_test(&first, &second) {
+        Ok(result) => writeln!(
+            output,
+            "KS distance: {}, p-value: {}",
+            format_number(result.statistic),
+            format_number(result.p_value)
+        )?,
+        Err(error) => writeln!(output, "KS: {error}")?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use histr::axis::GeneralAxis;
+
+    use super::*;
+    use crate::output::OutputFormat;
+
+    // Saves histograms with fixed-width bins in [0, 3] to a temporary directory.
+    fn save(name: &str, values: &[f64]) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("histr-cli-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(name);
+        let h1 = H1::new(
+            Box::new(FixedWidthAxis::new(0.0, 3.0, 1.0, 3)),
+            values.to_vec(),
+        );
+        h1.save(&path).unwrap();
+        path
+    }
+
+    fn output_args() -> OutputArgs {
+        OutputArgs {
+            format: OutputFormat::Csv,
+            vertical: false,
+            log: false,
+            errors: false,
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let files = vec![
+            save("merge_a.histr", &[1.0, 2.0, 3.0]),
+            save("merge_b.histr", &[0.0, 5.0, 1.0]),
+        ];
+        let sum_path = files[0].with_file_name("merge_sum.histr");
+        let args = MergeArgs {
+            files: files.clone(),
+            output: output_args(),
+            save: Some(sum_path.clone()),
+        };
+        let mut output = Vec::new();
+        merge(&args, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "lower,upper,center,content\n0,1,0.5,1\n1,2,1.5,7\n2,3,2.5,4\n"
+        );
+        assert_eq!(
+            H1::load(&sum_path).unwrap().bin_contents(),
+            &vec![1.0, 7.0, 4.0]
+        );
+
+        let general = files[0].with_file_name("merge_general.histr");
+        H1::new(
+            Box::new(GeneralAxis::new(vec![0.0, 1.0, 2.0, 3.0])),
+            vec![1.0; 3],
+        )
+        .save(&general)
+        .unwrap();
+        let args = MergeArgs {
+            files: vec![files[0].clone(), general],
+            output: output_args(),
+            save: None,
+        };
+        let error = merge(&args, &mut Vec::new()).unwrap_err().to_string();
+        assert!(
+            error.ends_with("(general axis with 3 bins in [0, 3] instead of fixed-width axis with 3 bins in [0, 3])."),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_info() {
+        let path = save("info.histr", &[1.0, 2.0, 1.0]);
+        let mut output = Vec::new();
+        info(
+            &InfoArgs {
+                files: vec![path.clone()],
+            },
+            &mut output,
+        )
+        .unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.starts_with(&format!(
+            "{}\n  axis:      fixed-width axis with 3 bins in [0, 3]\n",
+            path.display()
+        )));
+        assert!(text.contains("  total:     4\n"));
+        assert!(text.contains("  mean:      1.5\n"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let first = save("diff_a.histr", &[10.0, 20.0, 30.0]);
+        let second = save("diff_b.histr", &[15.0, 15.0, 30.0]);
+        let mut output = Vec::new();
+        diff(&DiffArgs { first, second }, &mut output).unwrap();
+        let expected = concat!(
+            "bin  lower  upper  first  second  difference\n",
+            "  0      0      1     10      15           5\n",
+            "  1      1      2     20      15          -5\n",
+            "  2      2      3     30      30           0\n",
+            "\n",
+            "chi2/ndf: 1.7143/2, p-value: 0.4244\n",
+            "KS distance: 0.0833, p-value: 0.9808\n",
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
@@ -0,0 +1,184 @@
+//! Reading values (and weights) from plain text or CSV / TSV tables.
+//!
+//! Plain text contains numbers separated by whitespace. Tables have one
+//! record per line. Columns are selected by name (which requires a header) or
+//! by 1-based index, in which case a first record that does not contain
+//! numbers is taken as the header. Lines starting with `#` are ignored
+//! in both formats.
+
+use std::error::Error;
+use std::io::BufRead;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    /// 0-based index of the column.
+    Index(usize),
+    Name(String),
+}
+
+impl FromStr for Column {
+    type Err = &'static str;
+
+    fn from_str(text: &str) -> Result<Self, &'static str> {
+        match text.parse::<usize>() {
+            Ok(0) => Err("Column indices start at 1."),
+            Ok(index) => Ok(Column::Index(index - 1)),
+            Err(_) if text.is_empty() => Err("Column name must not be empty."),
+            Err(_) => Ok(Column::Name(text.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Input {
+    pub values: Vec<f64>,
+    pub weights: Option<Vec<f64>>,
+}
+
+/// Numbers separated by whitespace.
+pub fn read_plain(reader: impl BufRead) -> Result<Input, Box<dyn Error>> {
+    let mut values = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        for token in line.split_whitespace() {
+            let value = token
+                .parse()
+                .map_err(|_| format!("Line {}: invalid number {token:?}.", i + 1))?;
+            values.push(value);
+        }
+    }
+    Ok(Input {
+        values,
+        weights: None,
+    })
+}
+
+/// Values (and optionally weights) from columns of a delimited table.
+pub fn read_table(
+    reader: impl BufRead,
+    delimiter: u8,
+    column: &Column,
+    weights: Option<&Column>,
+) -> Result<Input, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .comment(Some(b'#'))
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let mut records = reader.records().peekable();
+
+    let by_name = matches!(column, Column::Name(_)) || matches!(weights, Some(Column::Name(_)));
+    let header = match records.peek() {
+        Some(Ok(first)) => {
+            let is_header = by_name
+                || [Some(column), weights]
+                    .into_iter()
+                    .flatten()
+                    .any(|column| match column {
+                        Column::Index(index) => first
+                            .get(*index)
+                            .is_some_and(|field| field.parse::<f64>().is_err()),
+                        Column::Name(_) => true,
+                    });
+            is_header.then(|| first.clone())
+        }
+        _ => None,
+    };
+    if header.is_some() {
+        records.next();
+    }
+    let resolve = |column: &Column| -> Result<usize, Box<dyn Error>> {
+        match column {
+            Column::Index(index) => Ok(*index),
+            Column::Name(name) => header
+                .as_ref()
+                .and_then(|header| header.iter().position(|field| field == name))
+                .ok_or_else(|| format!("No column named {name:?}.").into()),
+        }
+    };
+    let value_index = resolve(column)?;
+    let weight_index = weights.map(resolve).transpose()?;
+
+    let mut input = Input {
+        values: Vec::new(),
+        weights: weight_index.map(|_| Vec::new()),
+    };
+    for record in records {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let field = |index: usize| -> Result<f64, Box<dyn Error>> {
+            let field = record
+                .get(index)
+                .ok_or_else(|| format!("Line {line}: no column {}.", index + 1))?;
+            field
+                .parse()
+                .map_err(|_| format!("Line {line}: invalid number {field:?}.").into())
+        };
+        input.values.push(field(value_index)?);
+        if let (Some(weights), Some(index)) = (input.weights.as_mut(), weight_index) {
+            weights.push(field(index)?);
+        }
+    }
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column() {
+        assert_eq!("2".parse(), Ok(Column::Index(1)));
+        assert_eq!("x".parse(), Ok(Column::Name("x".to_string())));
+        assert!("0".parse::<Column>().is_err());
+    }
+
+    #[test]
+    fn test_read_plain() {
+        let text = "# comment\n1 2.5\n\n  -3e1\n";
+        assert_eq!(
+            read_plain(text.as_bytes()).unwrap().values,
+            vec![1.0, 2.5, -30.0]
+        );
+
+        let error = read_plain("1\n2 x\n".as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: invalid number \"x\".");
+    }
+
+    #[test]
+    fn test_read_table_by_name() {
+        let text = "x,w\n1,0.5\n# comment\n2, 2\n";
+        let input = read_table(
+            text.as_bytes(),
+            b',',
+            &Column::Name("x".to_string()),
+            Some(&Column::Name("w".to_string())),
+        )
+        .unwrap();
+        assert_eq!(input.values, vec![1.0, 2.0]);
+        assert_eq!(input.weights, Some(vec![0.5, 2.0]));
+
+        assert!(read_table(text.as_bytes(), b',', &Column::Name("y".to_string()), None).is_err());
+    }
+
+    #[test]
+    fn test_read_table_by_index() {
+        let with_header = "a\tb\n1\t2\n3\t4\n";
+        let input = read_table(with_header.as_bytes(), b'\t', &Column::Index(1), None).unwrap();
+        assert_eq!(input.values, vec![2.0, 4.0]);
+        assert_eq!(input.weights, None);
+
+        let without_header = "1\t2\n3\t4\n";
+        let input = read_table(without_header.as_bytes(), b'\t', &Column::Index(0), None).unwrap();
+        assert_eq!(input.values, vec![1.0, 3.0]);
+
+        let error = read_table("1,2\n3\n".as_bytes(), b',', &Column::Index(1), None).unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: no column 2.");
+    }
+}
@@ -0,0 +1,417 @@
+//! Command-line tool for histogramming numbers from files or standard input.
+
+mod commands;
+mod input;
+mod output;
+
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use histr::axis::{Axis, GeneralAxis};
+use histr::binnings::{BinningAlgorithm, BinningRule, FixedWidthBins, PrettyBins, StandardBins};
+use histr::render::ColorChoice;
+use histr::H1;
+
+use crate::commands::{diff, info, merge, DiffArgs, InfoArgs, MergeArgs};
+use crate::input::{read_plain, read_table, Column, Input};
+use crate::output::{write_output, OutputFormat, OutputOptions};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "histr",
+    version,
+    about = "Histogram numbers from files or standard input.",
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    fill: FillArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Sum histograms with identical axes.
+    Merge(MergeArgs),
+    /// Print the axis, totals and moments of histograms.
+    Info(InfoArgs),
+    /// Compare two histograms bin by bin, with chi-square and Kolmogorov-Smirnov tests.
+    Diff(DiffArgs),
+}
+
+#[derive(Debug, Args)]
+struct FillArgs {
+    /// Input file, standard input if missing or "-".
+    file: Option<PathBuf>,
+
+    /// Column with the values, by name or 1-based index (reads the input as a table).
+    #[arg(short, long)]
+    column: Option<Column>,
+
+    /// Column with the weights, by name or 1-based index (reads the input as a table).
+    #[arg(short = 'W', long)]
+    weights: Option<Column>,
+
+    /// Field delimiter of tables [default: tab for .tsv files, comma otherwise].
+    #[arg(short, long)]
+    delimiter: Option<char>,
+
+    #[command(flatten)]
+    binning: BinningArgs,
+
+    /// Only bin values in a range, as MIN,MAX.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        allow_hyphen_values = true,
+        conflicts_with = "edges"
+    )]
+    range: Option<Vec<f64>>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Also save the histogram to a file in the histr format.
+    #[arg(short = 'o', long, value_name = "FILE")]
+    save: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct OutputArgs {
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Draw a vertical bar chart.
+    #[arg(long)]
+    vertical: bool,
+
+    /// Logarithmic scale of the bar chart.
+    #[arg(long)]
+    log: bool,
+
+    /// Show errors of the bin contents.
+    #[arg(long)]
+    errors: bool,
+}
+
+impl OutputArgs {
+    fn options(&self) -> OutputOptions {
+        OutputOptions {
+            format: self.format,
+            vertical: self.vertical,
+            log_scale: self.log,
+            errors: self.errors,
+            color: ColorChoice::Auto,
+        }
+    }
+}
+
+/// Binning, pretty bins with about 12 bins if none is given.
+#[derive(Debug, Args)]
+#[group(multiple = false)]
+struct BinningArgs {
+    /// Number of bins of equal width.
+    #[arg(short, long)]
+    bins: Option<usize>,
+
+    /// Width of the bins.
+    #[arg(long, allow_hyphen_values = true)]
+    width: Option<f64>,
+
+    /// Rule for the number of bins: sqrt, sturges, rice, scott, fd or doane.
+    #[arg(long)]
+    rule: Option<BinningRule>,
+
+    /// Approximate number of bins with "pretty" edges.
+    #[arg(long)]
+    pretty: Option<usize>,
+
+    /// Bin edges, comma separated.
+    #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+    edges: Option<Vec<f64>>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    let result = match &cli.command {
+        None => fill(&cli.fill, stdin.lock(), &mut output),
+        Some(Command::Merge(args)) => merge(args, &mut output),
+        Some(Command::Info(args)) => info(args, &mut output),
+        Some(Command::Diff(args)) => diff(args, &mut output),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("histr: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn fill(
+    args: &FillArgs,
+    stdin: impl BufRead,
+    output: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let input = match args.file.as_deref().filter(|file| file.as_os_str() != "-") {
+        Some(path) => {
+            let reader = BufReader::new(
+                File::open(path).map_err(|error| format!("{}: {error}", path.display()))?,
+            );
+            read_input(args, reader, path.extension())?
+        }
+        None => read_input(args, stdin, None)?,
+    };
+    if input.values.is_empty() {
+        return Err("No values in the input.".into());
+    }
+
+    let axis = find_axis(&args.binning, range(args)?, &input.values)?;
+    let n_bins = axis.len();
+    let mut h1 = H1::new(axis, vec![0.0; n_bins]);
+    match &input.weights {
+        Some(weights) => h1.fill_weighted_many(&input.values, weights)?,
+        None => h1.fill_many(&input.values),
+    }
+
+    if let Some(path) = &args.save {
+        h1.save(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+    }
+    write_output(&h1, &args.output.options(), output)
+}
+
+fn read_input(
+    args: &FillArgs,
+    reader: impl BufRead,
+    extension: Option<&OsStr>,
+) -> Result<Input, Box<dyn Error>> {
+    let is_tsv = extension.is_some_and(|extension| extension.eq_ignore_ascii_case("tsv"));
+    let is_csv = extension.is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+    if args.column.is_none()
+        && args.weights.is_none()
+        && args.delimiter.is_none()
+        && !is_tsv
+        && !is_csv
+    {
+        return read_plain(reader);
+    }
+    let delimiter = args.delimiter.unwrap_or(if is_tsv { '\t' } else { ',' });
+    let delimiter = u8::try_from(delimiter).map_err(|_| "Delimiter must be an ASCII character.")?;
+    let column = args.column.clone().unwrap_or(Column::Index(0));
+    read_table(reader, delimiter, &column, args.weights.as_ref())
+}
+
+fn range(args: &FillArgs) -> Result<Option<(f64, f64)>, Box<dyn Error>> {
+    match args.range.as_deref() {
+        None => Ok(None),
+        Some(&[min, max]) if min.is_finite() && max.is_finite() && min < max => {
+            Ok(Some((min, max)))
+        }
+        Some(&[_, _]) => Err("Range must be finite with MIN < MAX.".into()),
+        Some(_) => Err("Range must be given as MIN,MAX.".into()),
+    }
+}
+
+fn find_axis(
+    binning: &BinningArgs,
+    range: Option<(f64, f64)>,
+    values: &[f64],
+) -> Result<Box<dyn Axis>, Box<dyn Error>> {
+    fn find<B: BinningAlgorithm>(
+        binning: &B,
+        values: &[f64],
+        range: Option<(f64, f64)>,
+    ) -> Result<Box<dyn Axis>, Box<dyn Error>> {
+        let axis = match range {
+            Some(range) => binning.find_axis_in_range(values, range),
+            None => binning.find_axis(values),
+        };
+        axis.map(|axis| axis as Box<dyn Axis>)
+            .map_err(|error| error.to_string().into())
+    }
+
+    if let Some(edges) = &binning.edges {
+        if edges.len() < 2 {
+            return Err("At least two edges are required.".into());
+        }
+        if edges.iter().any(|edge| !edge.is_finite())
+            || edges.windows(2).any(|pair| pair[0] >= pair[1])
+        {
+            return Err("Edges must be finite and increasing.".into());
+        }
+        return Ok(Box::new(GeneralAxis::new(edges.clone())));
+    }
+    if binning.bins == Some(0) {
+        return Err("Number of bins must be positive.".into());
+    }
+    if binning
+        .width
+        .is_some_and(|width| !(width.is_finite() && width > 0.0))
+    {
+        return Err("Bin width must be positive.".into());
+    }
+    if binning.pretty.is_some_and(|approx_bins| approx_bins < 2) {
+        return Err("Approximate number of pretty bins must be at least 2.".into());
+    }
+    // Constant data would give bins of zero width
+    let range = range.or_else(|| {
+        let mut finite = values.iter().copied().filter(|value| value.is_finite());
+        let first = finite.next()?;
+        finite
+            .all(|value| value == first)
+            .then_some((first - 0.5, first + 0.5))
+    });
+    if let Some(n_bins) = binning.bins {
+        find(&StandardBins { n_bins }, values, range)
+    } else if let Some(bin_width) = binning.width {
+        find(&FixedWidthBins { bin_width }, values, range)
+    } else if let Some(rule) = binning.rule {
+        find(&rule, values, range)
+    } else {
+        let approx_bins = binning.pretty.unwrap_or(12);
+        find(&PrettyBins { approx_bins }, values, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str], stdin: &str) -> Result<String, Box<dyn Error>> {
+        let cli = Cli::try_parse_from(std::iter::once("histr").chain(args.iter().copied()))?;
+        let mut output = Vec::new();
+        fill(&cli.fill, stdin.as_bytes(), &mut output)?;
+        Ok(String::from_utf8(output)?)
+    }
+
+    #[test]
+    fn test_binning_flags() {
+        let data = "1 2 2 3 4\n";
+        let csv = run(&["--bins", "3", "-f", "csv"], data).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.ends_with("3,4,3.5,2\n"));
+
+        let csv = run(&["--edges", "-1,2,5", "-f", "csv"], data).unwrap();
+        assert_eq!(csv, "lower,upper,center,content\n-1,2,0.5,1\n2,5,3.5,4\n");
+
+        let csv = run(&["--width", "2", "--range", "0,4", "-f", "csv"], data).unwrap();
+        assert_eq!(csv, "lower,upper,center,content\n0,2,1,1\n2,4,3,4\n");
+
+        assert!(run(&["--rule", "sturges"], data).is_ok());
+        assert!(run(&["--rule", "magic"], data).is_err());
+        assert!(run(&["--bins", "3", "--width", "1"], data).is_err());
+    }
+
+    #[test]
+    fn test_weights() {
+        let data = "x;w\n0.5;2\n1.5;0.5\n1.7;1\n";
+        let csv = run(
+            &[
+                "-d", ";", "-c", "x", "-W", "w", "--edges", "0,1,2", "-f", "csv", "--errors",
+            ],
+            data,
+        )
+        .unwrap();
+        assert_eq!(
+            csv,
+            "lower,upper,center,content,error\n0,1,0.5,2,2\n1,2,1.5,1.5,1.118033988749895\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            run(&[], "").unwrap_err().to_string(),
+            "No values in the input."
+        );
+        assert_eq!(
+            run(&["--edges", "1"], "1").unwrap_err().to_string(),
+            "At least two edges are required."
+        );
+        assert_eq!(
+            run(&["--range", "1"], "1").unwrap_err().to_string(),
+            "Range must be given as MIN,MAX."
+        );
+    }
+
+    #[test]
+    fn test_invalid_edges_and_range() {
+        let error = |args: &[&str]| run(args, "1\n").unwrap_err().to_string();
+        for edges in ["0,nan", "nan,1", "0,inf", "1,0", "0,1,1"] {
+            assert_eq!(
+                error(&["--edges", edges]),
+                "Edges must be finite and increasing.",
+                "{edges}"
+            );
+        }
+        for range in ["2,1", "1,1", "0,nan", "-inf,1"] {
+            assert_eq!(
+                error(&["--range", range]),
+                "Range must be finite with MIN < MAX.",
+                "{range}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_binning() {
+        let data = "1 2 3\n";
+        let error = |args: &[&str]| run(args, data).unwrap_err().to_string();
+        assert_eq!(error(&["--bins", "0"]), "Number of bins must be positive.");
+        assert_eq!(error(&["--width", "0"]), "Bin width must be positive.");
+        assert_eq!(error(&["--width", "inf"]), "Bin width must be positive.");
+        assert_eq!(
+            error(&["--pretty", "0"]),
+            "Approximate number of pretty bins must be at least 2."
+        );
+        assert_eq!(
+            error(&["--pretty", "1"]),
+            "Approximate number of pretty bins must be at least 2."
+        );
+
+        // Taken as a value, not as an unknown flag
+        assert_eq!(error(&["--width", "-1"]), "Bin width must be positive.");
+    }
+
+    #[test]
+    fn test_constant_values() {
+        let csv = run(&["--bins", "3", "-f", "csv"], "2\n").unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("lower,upper,center,content\n1.5,"));
+        assert!(csv.contains("\n1.8333333333333333,2.1666666666666665,2,1\n"));
+        assert!(csv.ends_with(",2.5,2.333333333333333,0\n"));
+
+        for args in [
+            &["--rule", "fd", "-f", "csv"][..],
+            &["-f", "csv"],
+            &["--width", "0.5", "-f", "csv"],
+        ] {
+            let csv = run(args, "2 2 2 2\n").unwrap();
+            let contents: Vec<f64> = csv
+                .lines()
+                .skip(1)
+                .map(|line| {
+                    let fields: Vec<f64> = line
+                        .split(',')
+                        .map(|field| field.parse().unwrap())
+                        .collect();
+                    assert!(fields[1] > fields[0], "{csv}");
+                    fields[3]
+                })
+                .collect();
+            assert_eq!(contents.iter().sum::<f64>(), 4.0, "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Writing histograms in the output formats of the command-line tool.
+
+use std::error::Error;
+use std::io::Write;
+
+use clap::ValueEnum;
+use histr::render::{render_text, ColorChoice, TextOptions};
+use histr::table::{write_table, TableOptions};
+use histr::uhi::to_uhi_json;
+use histr::H1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Bar chart.
+    Text,
+    /// Aligned table with one row per bin.
+    Table,
+    /// UHI serialization.
+    Json,
+    Csv,
+    Tsv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub vertical: bool,
+    pub log_scale: bool,
+    pub errors: bool,
+    pub color: ColorChoice,
+}
+
+pub fn write_output(
+    h1: &H1,
+    options: &OutputOptions,
+    writer: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    match options.format {
+        OutputFormat::Text => {
+            let text_options = TextOptions {
+                log_scale: options.log_scale,
+                errors: options.errors,
+                color: options.color,
+                ..if options.vertical {
+                    TextOptions::vertical()
+                } else {
+                    TextOptions::default()
+                }
+            };
+            write!(writer, "{}", render_text(h1, &text_options))?;
+        }
+        OutputFormat::Table => write_aligned_table(h1, options.errors, writer)?,
+        OutputFormat::Json => writeln!(writer, "{}", to_uhi_json(h1))?,
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let table_options = TableOptions {
+                errors: options.errors,
+                ..if options.format == OutputFormat::Tsv {
+                    TableOptions::tsv()
+                } else {
+                    TableOptions::default()
+                }
+            };
+            write_table(h1, writer, &table_options)?;
+        }
+    }
+    Ok(())
+}
+
+// Table for reading in a terminal, with underflow and overflow if not empty.
+fn write_aligned_table(h1: &H1, errors: bool, writer: &mut impl Write) -> std::io::Result<()> {
+    let edges = h1.axis().bin_edges();
+    let values = h1.values();
+    let variances = h1.variances();
+    let mut rows: Vec<Vec<String>> = vec![["bin", "lower", "upper", "content"]
+        .map(String::from)
+        .to_vec()];
+    if errors {
+        rows[0].push("error".to_string());
+    }
+    for (i, value) in values.iter().enumerate() {
+        let mut row = vec![
+            i.to_string(),
+            edges[i].to_string(),
+            edges[i + 1].to_string(),
+            value.to_string(),
+        ];
+        if errors {
+            row.push(variances[i].sqrt().to_string());
+        }
+        rows.push(row);
+    }
+    for (name, flow) in [("underflow", h1.underflow()), ("overflow", h1.overflow())] {
+        if flow.value != 0.0 {
+            let mut row = vec![
+                name.to_string(),
+                String::new(),
+                String::new(),
+                flow.value.to_string(),
+            ];
+            if errors {
+                row.push(flow.variance.sqrt().to_string());
+            }
+            rows.push(row);
+        }
+    }
+    write_rows(&rows, writer)
+}
+
+/// Rows of fields, right-aligned in columns.
+pub fn write_rows(rows: &[Vec<String>], writer: &mut impl Write) -> std::io::Result<()> {
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect();
+    for row in rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(field, width)| format!("{field:>width$}"))
+            .collect();
+        writeln!(writer, "{}", line.join("  "))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use histr::axis::GeneralAxis;
+
+    use super::*;
+
+    fn write(format: OutputFormat, errors: bool) -> String {
+        let mut h1 = H1::new(
+            Box::new(GeneralAxis::new(vec![0.0, 1.0, 2.5])),
+            vec![0.0; 2],
+        );
+        h1.fill_many(&[0.5, 1.0, 2.0, 3.0]);
+        let options = OutputOptions {
+            format,
+            vertical: false,
+            log_scale: false,
+            errors,
+            color: ColorChoice::Never,
+        };
+        let mut output = Vec::new();
+        write_output(&h1, &options, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_table() {
+        let expected = concat!(
+            "     bin  lower  upper  content\n",
+            "       0      0      1        1\n",
+            "       1      1    2.5        2\n",
+            "overflow                      1\n",
+        );
+        assert_eq!(write(OutputFormat::Table, false), expected);
+    }
+
+    #[test]
+    fn test_tsv() {
+        let expected = "lower\tupper\tcenter\tcontent\terror\n0\t1\t0.5\t1\t1\n1\t2.5\t1.75\t2\t1.4142135623730951\n";
+        assert_eq!(write(OutputFormat::Tsv, true), expected);
+    }
+}
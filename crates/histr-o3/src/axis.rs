@@ -0,0 +1,197 @@
+use histr::axis::{Axis, FixedWidthAxis, GeneralAxis};
+use numpy::PyArray1;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::PyClassInitializer;
+
+use crate::values::extract_values;
+
+/// Traits of an axis, as required by the UHI `PlottableAxis` protocol.
+#[pyclass(name = "AxisTraits", frozen)]
+pub struct PyAxisTraits;
+
+#[pymethods]
+impl PyAxisTraits {
+    #[getter]
+    fn circular(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn discrete(&self) -> bool {
+        false
+    }
+}
+
+#[pyclass(name = "Axis", subclass, frozen)]
+pub struct PyAxis {
+    pub inner: Box<dyn Axis>,
+}
+
+#[pymethods]
+impl PyAxis {
+    #[getter]
+    fn edges<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.bin_edges())
+    }
+
+    #[getter]
+    fn centers<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        let edges = self.inner.bin_edges();
+        let centers: Vec<f64> = edges
+            .windows(2)
+            .map(|pair| (pair[0] + pair[1]) / 2.0)
+            .collect();
+        PyArray1::from_vec(py, centers)
+    }
+
+    #[getter]
+    fn widths<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        let edges = self.inner.bin_edges();
+        let widths: Vec<f64> = edges.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        PyArray1::from_vec(py, widths)
+    }
+
+    #[getter]
+    fn traits(&self) -> PyAxisTraits {
+        PyAxisTraits
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[getter]
+    fn min_edge(&self) -> f64 {
+        self.inner.min_edge()
+    }
+
+    #[getter]
+    fn max_edge(&self) -> f64 {
+        self.inner.max_edge()
+    }
+
+    /// Index of the bin containing the value, None if outside of the axis.
+    fn find_bin(&self, value: f64) -> Option<usize> {
+        self.inner.find_bin(value)
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<(f64, f64)> {
+        let index = if index < 0 {
+            index + self.inner.len() as isize
+        } else {
+            index
+        };
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.inner.get_bin(index))
+            .ok_or_else(|| PyIndexError::new_err("Bin index out of range."))
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        match other.cast::<PyAxis>() {
+            Ok(other) => self.inner.equal_bins(other.get().inner.as_ref()),
+            Err(_) => false,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Axis({} bins from {} to {})",
+            self.inner.len(),
+            self.inner.min_edge(),
+            self.inner.max_edge()
+        )
+    }
+}
+
+#[pyclass(name = "GeneralAxis", extends = PyAxis, frozen)]
+pub struct PyGeneralAxis;
+
+#[pymethods]
+impl PyGeneralAxis {
+    #[new]
+    fn new(edges: &Bound<'_, PyAny>) -> PyResult<(Self, PyAxis)> {
+        let edges = extract_values(edges, "edges")?.as_f64().into_owned();
+        check_edges(&edges)?;
+        Ok((
+            PyGeneralAxis,
+            PyAxis {
+                inner: Box::new(GeneralAxis::new(edges)),
+            },
+        ))
+    }
+}
+
+#[pyclass(name = "FixedWidthAxis", extends = PyAxis, frozen)]
+pub struct PyFixedWidthAxis;
+
+#[pymethods]
+impl PyFixedWidthAxis {
+    #[new]
+    fn new(min_edge: f64, bin_width: f64, n_bins: usize) -> PyResult<(Self, PyAxis)> {
+        if !(min_edge.is_finite() && bin_width.is_finite() && bin_width > 0.0) || n_bins == 0 {
+            return Err(PyValueError::new_err("Invalid axis parameters."));
+        }
+        let axis = FixedWidthAxis::create_from_min_and_bins(min_edge, bin_width, n_bins);
+        Ok((
+            PyFixedWidthAxis,
+            PyAxis {
+                inner: Box::new(axis),
+            },
+        ))
+    }
+
+    #[staticmethod]
+    fn from_range(
+        py: Python<'_>,
+        min_edge: f64,
+        max_edge: f64,
+        bin_width: f64,
+    ) -> PyResult<Py<PyAny>> {
+        let valid_range = min_edge.is_finite() && max_edge.is_finite() && min_edge < max_edge;
+        if !(valid_range && bin_width.is_finite() && bin_width > 0.0) {
+            return Err(PyValueError::new_err("Invalid axis parameters."));
+        }
+        let axis = FixedWidthAxis::create_from_range(min_edge, max_edge, bin_width);
+        axis_to_py(py, Box::new(axis))
+    }
+
+    #[getter]
+    fn bin_width(self_: PyRef<'_, Self>) -> f64 {
+        let axis = self_
+            .as_super()
+            .inner
+            .as_any()
+            .downcast_ref::<FixedWidthAxis>();
+        axis.expect("FixedWidthAxis wraps a fixed-width axis.")
+            .bin_width()
+    }
+}
+
+pub fn check_edges(edges: &[f64]) -> PyResult<()> {
+    if edges.len() < 2 {
+        return Err(PyValueError::new_err("At least two edges are required."));
+    }
+    if edges
+        .windows(2)
+        .any(|pair| pair[0].is_nan() || pair[0] >= pair[1])
+    {
+        return Err(PyValueError::new_err("Edges must be increasing."));
+    }
+    Ok(())
+}
+
+/// Python object of the most specific axis class.
+pub fn axis_to_py(py: Python<'_>, axis: Box<dyn Axis>) -> PyResult<Py<PyAny>> {
+    let is_fixed_width = axis.as_any().is::<FixedWidthAxis>();
+    let is_general = axis.as_any().is::<GeneralAxis>();
+    let initializer = PyClassInitializer::from(PyAxis { inner: axis });
+    if is_fixed_width {
+        Ok(Py::new(py, initializer.add_subclass(PyFixedWidthAxis))?.into_any())
+    } else if is_general {
+        Ok(Py::new(py, initializer.add_subclass(PyGeneralAxis))?.into_any())
+    } else {
+        Ok(Py::new(py, initializer)?.into_any())
+    }
+}
@@ -0,0 +1,47 @@
+use histr::bin::Bin;
+use pyo3::prelude::*;
+
+#[pyclass(name = "Bin", frozen, eq)]
+#[derive(PartialEq)]
+pub struct PyBin {
+    #[pyo3(get)]
+    value: f64,
+    #[pyo3(get)]
+    lower: f64,
+    #[pyo3(get)]
+    upper: f64,
+}
+
+#[pymethods]
+impl PyBin {
+    #[new]
+    fn new(value: f64, lower: f64, upper: f64) -> Self {
+        PyBin {
+            value,
+            lower,
+            upper,
+        }
+    }
+
+    #[getter]
+    fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Bin(value={}, lower={}, upper={})",
+            self.value, self.lower, self.upper
+        )
+    }
+}
+
+impl From<Bin> for PyBin {
+    fn from(bin: Bin) -> Self {
+        PyBin {
+            value: bin.value,
+            lower: bin.lower,
+            upper: bin.upper,
+        }
+    }
+}
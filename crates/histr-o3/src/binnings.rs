@@ -0,0 +1,143 @@
+use histr::axis::Axis;
+use histr::binnings::{BinningAlgorithm, BinningRule, FixedWidthBins, PrettyBins, StandardBins};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::axis::axis_to_py;
+use crate::values::extract_values;
+
+#[derive(Clone, Copy)]
+pub enum Binning {
+    Standard(usize),
+    FixedWidth(f64),
+    Pretty(usize),
+    Rule(BinningRule),
+}
+
+impl Binning {
+    pub fn find_axis(&self, data: &[f64], range: Option<(f64, f64)>) -> PyResult<Box<dyn Axis>> {
+        fn find<B: BinningAlgorithm>(
+            binning: &B,
+            data: &[f64],
+            range: Option<(f64, f64)>,
+        ) -> PyResult<Box<dyn Axis>> {
+            let axis = match range {
+                Some(range) => binning.find_axis_in_range(data, range),
+                None => binning.find_axis(data),
+            };
+            axis.map(|axis| axis as Box<dyn Axis>)
+                .map_err(|error| PyValueError::new_err(error.to_string()))
+        }
+
+        match *self {
+            Binning::Standard(n_bins) => find(&StandardBins { n_bins }, data, range),
+            Binning::FixedWidth(bin_width) => find(&FixedWidthBins { bin_width }, data, range),
+            Binning::Pretty(approx_bins) => find(&PrettyBins { approx_bins }, data, range),
+            Binning::Rule(rule) => find(&rule, data, range),
+        }
+    }
+}
+
+#[pyclass(name = "BinningAlgorithm", subclass, frozen)]
+pub struct PyBinningAlgorithm {
+    pub inner: Binning,
+}
+
+#[pymethods]
+impl PyBinningAlgorithm {
+    /// Axis for the data, optionally limited to an explicit (min, max) range.
+    #[pyo3(signature = (data, range=None))]
+    fn find_axis(
+        &self,
+        py: Python<'_>,
+        data: &Bound<'_, PyAny>,
+        range: Option<(f64, f64)>,
+    ) -> PyResult<Py<PyAny>> {
+        let data = extract_values(data, "data")?;
+        axis_to_py(py, self.inner.find_axis(&data.as_f64(), range)?)
+    }
+}
+
+#[pyclass(name = "StandardBins", extends = PyBinningAlgorithm, frozen)]
+pub struct PyStandardBins;
+
+#[pymethods]
+impl PyStandardBins {
+    #[new]
+    fn new(n_bins: usize) -> PyResult<(Self, PyBinningAlgorithm)> {
+        if n_bins == 0 {
+            return Err(PyValueError::new_err("n_bins must be positive."));
+        }
+        let inner = Binning::Standard(n_bins);
+        Ok((PyStandardBins, PyBinningAlgorithm { inner }))
+    }
+
+    #[getter]
+    fn n_bins(self_: PyRef<'_, Self>) -> usize {
+        match self_.as_super().inner {
+            Binning::Standard(n_bins) => n_bins,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[pyclass(name = "FixedWidthBins", extends = PyBinningAlgorithm, frozen)]
+pub struct PyFixedWidthBins;
+
+#[pymethods]
+impl PyFixedWidthBins {
+    #[new]
+    fn new(bin_width: f64) -> PyResult<(Self, PyBinningAlgorithm)> {
+        if !(bin_width > 0.0 && bin_width.is_finite()) {
+            return Err(PyValueError::new_err("bin_width must be positive."));
+        }
+        let inner = Binning::FixedWidth(bin_width);
+        Ok((PyFixedWidthBins, PyBinningAlgorithm { inner }))
+    }
+
+    #[getter]
+    fn bin_width(self_: PyRef<'_, Self>) -> f64 {
+        match self_.as_super().inner {
+            Binning::FixedWidth(bin_width) => bin_width,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[pyclass(name = "PrettyBins", extends = PyBinningAlgorithm, frozen)]
+pub struct PyPrettyBins;
+
+#[pymethods]
+impl PyPrettyBins {
+    #[new]
+    #[pyo3(signature = (approx_bins=12))]
+    fn new(approx_bins: usize) -> PyResult<(Self, PyBinningAlgorithm)> {
+        if approx_bins < 2 {
+            return Err(PyValueError::new_err("approx_bins must be at least 2."));
+        }
+        let inner = Binning::Pretty(approx_bins);
+        Ok((PyPrettyBins, PyBinningAlgorithm { inner }))
+    }
+
+    #[getter]
+    fn approx_bins(self_: PyRef<'_, Self>) -> usize {
+        match self_.as_super().inner {
+            Binning::Pretty(approx_bins) => approx_bins,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Number of bins from a rule of numpy.histogram_bin_edges ("sqrt", "sturges",
+/// "rice", "scott", "fd" or "doane").
+#[pyclass(name = "RuleBins", extends = PyBinningAlgorithm, frozen)]
+pub struct PyRuleBins;
+
+#[pymethods]
+impl PyRuleBins {
+    #[new]
+    fn new(rule: &str) -> PyResult<(Self, PyBinningAlgorithm)> {
+        let inner = Binning::Rule(rule.parse().map_err(PyValueError::new_err)?);
+        Ok((PyRuleBins, PyBinningAlgorithm { inner }))
+    }
+}
@@ -0,0 +1,47 @@
+use histr::distance;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::PyH1;
+
+/// Kullback–Leibler divergence of the second histogram from the first.
+#[pyfunction]
+pub fn kl_divergence(first: &PyH1, second: &PyH1) -> PyResult<f64> {
+    distance::kl_divergence(&first.inner, &second.inner).map_err(PyValueError::new_err)
+}
+
+/// Jensen–Shannon divergence, bounded by ln 2.
+#[pyfunction]
+pub fn js_divergence(first: &PyH1, second: &PyH1) -> PyResult<f64> {
+    distance::js_divergence(&first.inner, &second.inner).map_err(PyValueError::new_err)
+}
+
+/// Hellinger distance, in [0, 1].
+#[pyfunction]
+pub fn hellinger_distance(first: &PyH1, second: &PyH1) -> PyResult<f64> {
+    distance::hellinger_distance(&first.inner, &second.inner).map_err(PyValueError::new_err)
+}
+
+/// Bhattacharyya distance.
+#[pyfunction]
+pub fn bhattacharyya_distance(first: &PyH1, second: &PyH1) -> PyResult<f64> {
+    distance::bhattacharyya_distance(&first.inner, &second.inner).map_err(PyValueError::new_err)
+}
+
+/// Wasserstein-1 (earth mover's) distance, with the contents at the bin centers.
+#[pyfunction]
+pub fn wasserstein_distance(first: &PyH1, second: &PyH1) -> PyResult<f64> {
+    distance::wasserstein_distance(&first.inner, &second.inner).map_err(PyValueError::new_err)
+}
+
+/// Histogram intersection of the normalized histograms, in [0, 1].
+#[pyfunction]
+pub fn intersection(first: &PyH1, second: &PyH1) -> PyResult<f64> {
+    distance::intersection(&first.inner, &second.inner).map_err(PyValueError::new_err)
+}
+
+/// Total variation distance, in [0, 1].
+#[pyfunction]
+pub fn total_variation(first: &PyH1, second: &PyH1) -> PyResult<f64> {
+    distance::total_variation(&first.inner, &second.inner).map_err(PyValueError::new_err)
+}
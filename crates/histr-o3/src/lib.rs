@@ -1,28 +1,135 @@
-use pyo3::exceptions::PyException;
+mod axis;
+mod bin;
+mod binnings;
+mod distance;
+mod values;
+
+use numpy::PyArray1;
+use pyo3::exceptions::{PyIOError, PyIndexError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use histr::axis::{Axis, GeneralAxis};
+use histr::plot::{plot_svg, PlotOptions, PlotStyle};
+use histr::render::{render_html, render_text, ColorChoice, Orientation, TextOptions};
 use histr::*;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use axis::{axis_to_py, check_edges, PyAxis, PyAxisTraits, PyFixedWidthAxis, PyGeneralAxis};
+use bin::PyBin;
+use binnings::{
+    Binning, PyBinningAlgorithm, PyFixedWidthBins, PyPrettyBins, PyRuleBins, PyStandardBins,
+};
+use values::{extract_values, with_slice, Values};
 
-#[pyclass(name = "H1")]
+#[pyclass(name = "H1", module = "histr._histr")]
 pub struct PyH1 {
     inner: H1<'static>,
 }
 
 #[pymethods]
 impl PyH1 {
+    /// Histogram over the axis, empty unless bin contents are given.
+    #[new]
+    #[pyo3(signature = (axis, contents=None))]
+    fn new(axis: &Bound<'_, PyAxis>, contents: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let axis = axis.get().inner.clone_box();
+        let contents = match contents {
+            Some(contents) => {
+                let contents = extract_values(contents, "contents")?.as_f64().into_owned();
+                if contents.len() != axis.len() {
+                    return Err(PyValueError::new_err(
+                        "Number of bin contents must match the number of bins.",
+                    ));
+                }
+                contents
+            }
+            None => vec![0.0; axis.len()],
+        };
+        Ok(PyH1 {
+            inner: H1::new(axis, contents),
+        })
+    }
+
     #[getter]
-    fn bin_contents<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
-        Ok(self.inner.bin_contents().clone().into_pyobject(py)?.into_any())
+    fn bin_contents<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, self.inner.bin_contents())
     }
 
     #[getter]
-    fn bin_edges<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
-        Ok(self.inner.axis().bin_edges().to_vec().into_pyobject(py)?.into_any())
+    fn bin_edges<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.axis().bin_edges())
+    }
+
+    #[pyo3(signature = (dtype=None, copy=None))]
+    fn __array__<'py>(
+        &self,
+        py: Python<'py>,
+        dtype: Option<Bound<'py, PyAny>>,
+        copy: Option<bool>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if copy == Some(false) {
+            return Err(PyValueError::new_err("Cannot create an array without copying."));
+        }
+        let array = self.bin_contents(py).into_any();
+        match dtype {
+            Some(dtype) => array.call_method1("astype", (dtype,)),
+            None => Ok(array),
+        }
     }
 
     fn __len__(&self) -> usize {
         self.inner.len()
     }
 
+    #[getter]
+    fn axis(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        axis_to_py(py, self.inner.axis().clone_box())
+    }
+
+    fn get_bin(&self, n: usize) -> PyResult<PyBin> {
+        self.inner
+            .get_bin(n)
+            .map(PyBin::from)
+            .ok_or_else(|| PyIndexError::new_err("Bin index out of range."))
+    }
+
+    // UHI PlottableHistogram protocol
+
+    #[getter]
+    fn kind(&self) -> &'static str {
+        "COUNT"
+    }
+
+    #[getter]
+    fn axes(&self, py: Python<'_>) -> PyResult<(Py<PyAny>,)> {
+        Ok((self.axis(py)?,))
+    }
+
+    fn values<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        self.bin_contents(py)
+    }
+
+    fn variances<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.variances())
+    }
+
+    /// Number of entries, effective one (sum_w ** 2 / sum_w2) for weighted fills.
+    fn counts<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        if !self.inner.has_variances() {
+            return self.bin_contents(py);
+        }
+        let counts: Vec<f64> = self
+            .inner
+            .bin_contents()
+            .iter()
+            .zip(self.inner.variances().iter())
+            .map(|(value, variance)| if *variance > 0.0 { value * value / variance } else { 0.0 })
+            .collect();
+        PyArray1::from_vec(py, counts)
+    }
+
     #[getter]
     fn total(&self) -> f64 {
         self.inner.total()
@@ -37,48 +144,294 @@ impl PyH1 {
     }
 
     #[pyo3(signature = (values, *, weights=None))]
-    fn fill_many(&mut self, py: Python<'_>, values: Py<PyAny>, weights: Option<Py<PyAny>>) -> PyResult<()> {
-        let values: Vec<f64> = values.extract(py).unwrap();
+    fn fill_many(&mut self, values: &Bound<'_, PyAny>, weights: Option<&Bound<'_, PyAny>>) -> PyResult<()> {
+        let values = extract_values(values, "values")?;
         match weights {
             Some(weights) => {
-                let weights: Vec<f64> = weights.extract(py)?;
-                self.inner.fill_weighted_many(&values, &weights).unwrap();
+                let weights = extract_values(weights, "weights")?;
+                let weights = weights.as_f64();
+                with_slice!(&values, |values| self.inner.fill_weighted_many(values, &weights))
+                    .map_err(PyValueError::new_err)?;
             }
-            None => {
-                self.inner.fill_many(&values);
+            None => with_slice!(&values, |values| self.inner.fill_many(values)),
+        }
+        Ok(())
+    }
+
+    /// Fill using all cores, without holding the GIL.
+    #[pyo3(signature = (values, *, weights=None))]
+    fn fill_many_par(
+        &mut self,
+        py: Python<'_>,
+        values: &Bound<'_, PyAny>,
+        weights: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        let values = extract_values(values, "values")?;
+        let inner = &mut self.inner;
+        match weights {
+            Some(weights) => {
+                let weights = extract_values(weights, "weights")?;
+                let weights = weights.as_f64();
+                with_slice!(&values, |values| py.detach(|| inner.fill_weighted_many_par(values, &weights)))
+                    .map_err(PyValueError::new_err)?;
             }
+            None => with_slice!(&values, |values| py.detach(|| inner.fill_many_par(values))),
         }
         Ok(())
     }
 
+    fn save(&self, path: PathBuf) -> PyResult<()> {
+        self.inner.save(path).map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn load(path: PathBuf) -> PyResult<PyH1> {
+        Ok(PyH1 {
+            inner: H1::load(path).map_err(to_py_err)?,
+        })
+    }
+
+    #[pyo3(signature = (path, *, errors=false, delimiter=','))]
+    fn to_csv(&self, path: PathBuf, errors: bool, delimiter: char) -> PyResult<()> {
+        let options = histr::table::TableOptions { delimiter, errors };
+        let mut writer = BufWriter::new(File::create(path)?);
+        histr::table::write_table(&self.inner, &mut writer, &options)?;
+        Ok(writer.flush()?)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (path, *, delimiter=','))]
+    fn from_csv(path: PathBuf, delimiter: char) -> PyResult<PyH1> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(PyH1 {
+            inner: histr::table::read_table(reader, delimiter).map_err(to_py_err)?,
+        })
+    }
+
+    /// Histogram in the binary histr format.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &histr::format::to_bytes(&self.inner))
+    }
+
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<PyH1> {
+        Ok(PyH1 {
+            inner: histr::format::from_bytes(data).map_err(to_py_err)?,
+        })
+    }
+
+    fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        let from_bytes = py.get_type::<PyH1>().getattr("from_bytes")?;
+        Ok((from_bytes, (self.to_bytes(py),)))
+    }
+
+    fn copy(&self) -> PyH1 {
+        PyH1 {
+            inner: self.inner.clone(),
+        }
+    }
+
+    fn __copy__(&self) -> PyH1 {
+        self.copy()
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> PyH1 {
+        self.copy()
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        match other.cast::<PyH1>() {
+            Ok(other) => self.inner == other.borrow().inner,
+            Err(_) => false,
+        }
+    }
+
+    /// Equality up to a tolerance on edges, contents, variances and flows (as math.isclose).
+    #[pyo3(signature = (other, *, rel_tol=1e-9, abs_tol=0.0))]
+    fn approx_eq(&self, other: &PyH1, rel_tol: f64, abs_tol: f64) -> bool {
+        self.inner.approx_eq(&other.inner, rel_tol, abs_tol)
+    }
+
+    /// Histogram in the UHI serialization schema, as a dictionary.
+    fn to_uhi<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let json = histr::uhi::to_uhi_json(&self.inner);
+        py.import("json")?.call_method1("loads", (json,))
+    }
+
+    #[staticmethod]
+    fn from_uhi(py: Python<'_>, data: Py<PyAny>) -> PyResult<PyH1> {
+        let json: String = py.import("json")?.call_method1("dumps", (data,))?.extract()?;
+        Ok(PyH1 {
+            inner: histr::uhi::from_uhi_json(&json).map_err(to_py_err)?,
+        })
+    }
+
+    /// Bar chart of the histogram as text.
+    #[pyo3(signature = (*, width=50, height=12, vertical=false, log=false, errors=false, ascii=false, color=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn to_text(
+        &self,
+        width: usize,
+        height: usize,
+        vertical: bool,
+        log: bool,
+        errors: bool,
+        ascii: bool,
+        color: Option<bool>,
+    ) -> String {
+        let options = TextOptions {
+            orientation: if vertical { Orientation::Vertical } else { Orientation::Horizontal },
+            width,
+            height,
+            log_scale: log,
+            errors,
+            unicode: !ascii,
+            color: match color {
+                Some(true) => ColorChoice::Always,
+                Some(false) => ColorChoice::Never,
+                None => ColorChoice::Auto,
+            },
+        };
+        render_text(&self.inner, &options)
+    }
+
+    /// Plot of the histogram as SVG, `style` is "step", "bar" or "errorbar".
+    #[pyo3(signature = (*, style="step", width=640, height=480, title=None, xlabel=None, ylabel=None, logx=false, logy=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn to_svg(
+        &self,
+        style: &str,
+        width: u32,
+        height: u32,
+        title: Option<String>,
+        xlabel: Option<String>,
+        ylabel: Option<String>,
+        logx: bool,
+        logy: bool,
+    ) -> PyResult<String> {
+        let style: PlotStyle = style.parse().map_err(PyValueError::new_err)?;
+        let options = PlotOptions {
+            width,
+            height,
+            title,
+            x_label: xlabel,
+            y_label: ylabel,
+            log_x: logx,
+            log_y: logy,
+            ..Default::default()
+        };
+        Ok(plot_svg(&self.inner, style, &options))
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn _repr_html_(&self) -> String {
+        render_html(&self.inner)
+    }
+
     fn __repr__(&self) -> String {
         format!("H1({} bins)", self.inner.len())
     }
 }
 
-#[pyfunction(name = "h1")]
-#[pyo3(signature = (data, *, bin_width=None, bin_edges=None))]
-fn py_h1(py: Python<'_>, data: Py<PyAny>, bin_width: Option<f64>, bin_edges: Option<Py<PyAny>>) -> PyResult<PyH1> {
-    let values: Vec<f64> = data.extract(py)?;
-    let h1 = match bin_width {
-        Some(bin_width) => h1!(&values, bin_width: bin_width),
-        None => match bin_edges {
-            Some(bin_edges) => {
-                let bin_edges: Vec<f64> = bin_edges.extract(py)?;
-                Ok(h1!(&values, bin_edges: &bin_edges))
+fn to_py_err(error: Box<dyn Error>) -> PyErr {
+    match error.downcast::<std::io::Error>() {
+        Ok(error) => PyIOError::new_err(error.to_string()),
+        Err(error) => PyValueError::new_err(error.to_string()),
+    }
+}
+
+/// Binning from the `bins` argument of `h1()`.
+///
+/// An int is a number of equal bins, a string names a rule ("pretty" or one
+/// of numpy.histogram_bin_edges rules) and a sequence gives the bin edges.
+fn find_axis(
+    values: &[f64],
+    bins: Option<&Bound<'_, PyAny>>,
+    bin_width: Option<f64>,
+    range: Option<(f64, f64)>,
+) -> PyResult<Box<dyn Axis>> {
+    let binning = match (bins, bin_width) {
+        (Some(_), Some(_)) => {
+            return Err(PyValueError::new_err("Cannot use both bins and bin_width."));
+        }
+        (None, Some(bin_width)) => Binning::FixedWidth(bin_width),
+        (None, None) => Binning::Pretty(12),
+        (Some(bins), None) => {
+            if let Ok(algorithm) = bins.cast::<PyBinningAlgorithm>() {
+                algorithm.get().inner
+            } else if let Ok(name) = bins.cast::<PyString>() {
+                match name.to_str()? {
+                    "pretty" => Binning::Pretty(12),
+                    name => Binning::Rule(name.parse().map_err(PyValueError::new_err)?),
+                }
+            } else if let Ok(n_bins) = bins.extract::<usize>() {
+                if n_bins == 0 {
+                    return Err(PyValueError::new_err("Number of bins must be positive."));
+                }
+                Binning::Standard(n_bins)
+            } else {
+                if range.is_some() {
+                    return Err(PyValueError::new_err("Cannot use range with explicit bin edges."));
+                }
+                let edges = extract_values(bins, "bins")?.as_f64().into_owned();
+                check_edges(&edges)?;
+                return Ok(Box::new(GeneralAxis::new(edges)));
             }
-            None => h1!(&values),
         }
     };
-    match h1 {
-        Ok(h1) => Ok(PyH1 { inner: h1 }),
-        Err(_e) => Err(PyErr::new::<PyException, _>("Error creating H1")),
-    }
+    binning.find_axis(values, range)
+}
+
+#[pyfunction(name = "h1")]
+#[pyo3(signature = (data, bins=None, *, range=None, bin_width=None, bin_edges=None))]
+fn py_h1(
+    data: &Bound<'_, PyAny>,
+    bins: Option<&Bound<'_, PyAny>>,
+    range: Option<(f64, f64)>,
+    bin_width: Option<f64>,
+    bin_edges: Option<&Bound<'_, PyAny>>,
+) -> PyResult<PyH1> {
+    let values = extract_values(data, "data")?;
+    let bins = match (bins, bin_edges) {
+        (Some(_), Some(_)) => {
+            return Err(PyValueError::new_err("Cannot use both bins and bin_edges."));
+        }
+        (bins, bin_edges) => bins.or(bin_edges),
+    };
+    // Binning algorithms work on f64, the filling itself does not need a copy
+    let axis = find_axis(&values.as_f64(), bins, bin_width, range)?;
+    let n_bins = axis.len();
+    let mut h1 = H1::new(axis, vec![0.0; n_bins]);
+    with_slice!(&values, |values| h1.fill_many(values));
+    Ok(PyH1 { inner: h1 })
 }
 
 #[pymodule(name="_histr")]
 fn _histr(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyH1>()?;
+    m.add_class::<PyAxis>()?;
+    m.add_class::<PyAxisTraits>()?;
+    m.add_class::<PyGeneralAxis>()?;
+    m.add_class::<PyFixedWidthAxis>()?;
+    m.add_class::<PyBin>()?;
+    m.add_class::<PyBinningAlgorithm>()?;
+    m.add_class::<PyStandardBins>()?;
+    m.add_class::<PyFixedWidthBins>()?;
+    m.add_class::<PyPrettyBins>()?;
+    m.add_class::<PyRuleBins>()?;
     m.add_function(wrap_pyfunction!(py_h1, m)?)?;
+    m.add_function(wrap_pyfunction!(distance::kl_divergence, m)?)?;
+    m.add_function(wrap_pyfunction!(distance::js_divergence, m)?)?;
+    m.add_function(wrap_pyfunction!(distance::hellinger_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(distance::bhattacharyya_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(distance::wasserstein_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(distance::intersection, m)?)?;
+    m.add_function(wrap_pyfunction!(distance::total_variation, m)?)?;
     Ok(())
 }
@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+
+use histr::fillable::to_f64_values;
+use numpy::{PyReadonlyArray1, PyUntypedArray, PyUntypedArrayMethods};
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+
+/// Numbers passed from Python.
+///
+/// Contiguous one-dimensional NumPy arrays of numeric dtypes are borrowed
+/// without copying, keeping their element type; non-contiguous arrays and
+/// other sequences are converted to `f64`.
+pub enum Values<'py> {
+    F64(PyReadonlyArray1<'py, f64>),
+    F32(PyReadonlyArray1<'py, f32>),
+    I8(PyReadonlyArray1<'py, i8>),
+    I16(PyReadonlyArray1<'py, i16>),
+    I32(PyReadonlyArray1<'py, i32>),
+    I64(PyReadonlyArray1<'py, i64>),
+    U8(PyReadonlyArray1<'py, u8>),
+    U16(PyReadonlyArray1<'py, u16>),
+    U32(PyReadonlyArray1<'py, u32>),
+    U64(PyReadonlyArray1<'py, u64>),
+    Owned(Vec<f64>),
+}
+
+/// Evaluates `$body` with `$slice` bound to the values as `&[T]` of their own type.
+macro_rules! with_slice {
+    ($values:expr, |$slice:ident| $body:expr) => {
+        with_slice!(@variants $values, $slice, $body, F64 F32 I8 I16 I32 I64 U8 U16 U32 U64)
+    };
+    (@variants $values:expr, $slice:ident, $body:expr, $($variant:ident)*) => {
+        match $values {
+            $(
+                Values::$variant(array) => {
+                    let $slice = array.as_slice().expect("Array is contiguous.");
+                    $body
+                }
+            )*
+            Values::Owned(values) => {
+                let $slice = values.as_slice();
+                $body
+            }
+        }
+    };
+}
+
+pub(crate) use with_slice;
+
+impl Values<'_> {
+    /// The values as `f64`, borrowed for float64 arrays.
+    pub fn as_f64(&self) -> Cow<'_, [f64]> {
+        with_slice!(self, |slice| to_f64_values(slice))
+    }
+}
+
+macro_rules! try_borrow {
+    ($obj:expr, $($t:ty => $variant:ident),*) => {
+        $(
+            if let Ok(array) = $obj.extract::<PyReadonlyArray1<'_, $t>>() {
+                return Ok(if array.is_contiguous() {
+                    Values::$variant(array)
+                } else {
+                    Values::Owned(array.as_array().iter().map(|&value| value as f64).collect())
+                });
+            }
+        )*
+    };
+}
+
+pub fn extract_values<'py>(obj: &Bound<'py, PyAny>, name: &str) -> PyResult<Values<'py>> {
+    try_borrow!(
+        obj,
+        f64 => F64, f32 => F32,
+        i8 => I8, i16 => I16, i32 => I32, i64 => I64,
+        u8 => U8, u16 => U16, u32 => U32, u64 => U64
+    );
+    if let Ok(array) = obj.cast::<PyUntypedArray>() {
+        return Err(PyTypeError::new_err(format!(
+            "{name} must be a one-dimensional numeric array, got {}-dimensional array of {}.",
+            array.ndim(),
+            array.dtype()
+        )));
+    }
+    obj.extract::<Vec<f64>>()
+        .map(Values::Owned)
+        .map_err(|_| PyTypeError::new_err(format!("{name} must be a sequence of numbers.")))
+}
@@ -0,0 +1,76 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use histr::axis::{Axis, FixedWidthAxis, GeneralAxis};
+use histr::H1;
+
+// Deterministic values spread over [-1, 11)
+fn get_values(n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| ((i as u64 * 2_654_435_761) % 1_000_003) as f64 / 83_333.6 - 1.0)
+        .collect()
+}
+
+fn bench_find_bins(c: &mut Criterion) {
+    let axis = FixedWidthAxis::create_from_min_and_bins(0.0, 0.1, 100);
+    let general_axis = GeneralAxis::new(axis.bin_edges().into_owned());
+    let mut group = c.benchmark_group("apply");
+    for n in [1_000, 100_000] {
+        let values = get_values(n);
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(
+            BenchmarkId::new("fixed_width_simd", n),
+            &values,
+            |b, values| b.iter(|| axis.apply(black_box(values))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("fixed_width_scalar", n),
+            &values,
+            |b, values| {
+                let axis: &dyn Axis = &axis;
+                b.iter(|| {
+                    let mut result = vec![0.0; axis.len()];
+                    for value in black_box(values) {
+                        if let Some(bin) = axis.find_bin(*value) {
+                            result[bin] += 1.0;
+                        }
+                    }
+                    result
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("general", n), &values, |b, values| {
+            b.iter(|| general_axis.apply(black_box(values)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_fill_many(c: &mut Criterion) {
+    let values = get_values(100_000);
+    let weights = vec![0.5; values.len()];
+    let mut h1 = H1::new(
+        Box::new(FixedWidthAxis::create_from_min_and_bins(0.0, 0.1, 100)),
+        vec![0.0; 100],
+    );
+    let mut group = c.benchmark_group("fill_many");
+    group.throughput(Throughput::Elements(values.len() as u64));
+    group.bench_function("unweighted", |b| {
+        b.iter(|| h1.fill_many(black_box(&values)))
+    });
+    group.bench_function("weighted", |b| {
+        b.iter(|| h1.fill_weighted_many(black_box(&values), black_box(&weights)))
+    });
+    let integers: Vec<i32> = values.iter().map(|value| (value * 10.0) as i32).collect();
+    let mut integer_h1 = H1::new(
+        Box::new(FixedWidthAxis::create_from_min_and_bins(0.0, 1.0, 100)),
+        vec![0.0; 100],
+    );
+    group.bench_function("integers", |b| {
+        b.iter(|| integer_h1.fill_many(black_box(&integers)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_find_bins, bench_fill_many);
+criterion_main!(benches);
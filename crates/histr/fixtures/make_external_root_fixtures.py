@@ -0,0 +1,68 @@
+"""Write external_th1.root with ROOT (PyROOT) if available, else with uproot.
+
+Unlike make_root_fixtures.py, the file is encoded by an independent writer.
+It holds a TH1D "h_d" with fixed-width bins and a TH1F "h_f" with variable
+bins, both with sum of squared weights, filled with the same weighted values
+in both writers; the expected contents are asserted in `histr::root`.
+
+Run from this directory: python make_external_root_fixtures.py
+"""
+
+import numpy as np
+
+FILE_NAME = "external_th1.root"
+
+FILLS_D = [(-1.0, 1.0), (0.2, 1.0), (0.7, 1.0), (0.8, 2.0), (1.9, 1.0), (2.5, 0.5), (1.2, 3.0)]
+FILLS_F = [(0.5, 2.0), (5.0, 1.0), (5.0, 0.5), (50.0, 1.0), (200.0, 1.5), (-3.0, 1.0)]
+EDGES_F = [0.0, 1.0, 10.0, 100.0]
+
+
+def write_with_root():
+    import ROOT
+
+    f = ROOT.TFile(FILE_NAME, "RECREATE")
+    h_d = ROOT.TH1D("h_d", "Fixed", 4, 0.0, 2.0)
+    h_f = ROOT.TH1F("h_f", "Variable", 3, np.array(EDGES_F))
+    for h, fills in [(h_d, FILLS_D), (h_f, FILLS_F)]:
+        h.Sumw2()
+        for x, w in fills:
+            h.Fill(x, w)
+        h.Write()
+    f.Close()
+
+
+def write_with_uproot():
+    import uproot
+    from uproot.writing.identify import to_TAxis, to_TH1x
+
+    def th1(name, title, edges, fills, dtype, fixed):
+        edges = np.asarray(edges, dtype=np.float64)
+        # With underflow and overflow
+        index = lambda x: np.searchsorted(edges, x, side="right")
+        sumw = np.zeros(len(edges) + 1)
+        sumw2 = np.zeros(len(edges) + 1)
+        for x, w in fills:
+            sumw[index(x)] += w
+            sumw2[index(x)] += w * w
+        inside = [(x, w) for x, w in fills if edges[0] <= x < edges[-1]]
+        axis = to_TAxis(
+            "xaxis", "", len(edges) - 1, edges[0], edges[-1],
+            fXbins=np.array([]) if fixed else edges,
+        )
+        return to_TH1x(
+            name, title, sumw.astype(dtype), len(fills),
+            sum(w for _, w in inside), sum(w * w for _, w in inside),
+            sum(w * x for x, w in inside), sum(w * x * x for x, w in inside),
+            sumw2, axis,
+        )
+
+    with uproot.recreate(FILE_NAME) as f:
+        f["h_d"] = th1("h_d", "Fixed", np.linspace(0.0, 2.0, 5), FILLS_D, np.float64, True)
+        f["h_f"] = th1("h_f", "Variable", EDGES_F, FILLS_F, np.float32, False)
+
+
+if __name__ == "__main__":
+    try:
+        write_with_root()
+    except ImportError:
+        write_with_uproot()
@@ -0,0 +1,46 @@
+"""Generate the histr format files used in the tests of `histr::format`.
+
+The files are encoded from the description of the format, not by the Rust
+writer, and are kept as they are to check that old versions stay readable.
+
+Run from this directory: python make_histr_fixtures.py
+"""
+
+import struct
+
+MAGIC = b"\x89HISTR\r\n"
+
+
+def section(tag, payload):
+    return tag + struct.pack("<Q", len(payload)) + payload
+
+
+def f64s(values):
+    return struct.pack("<Q", len(values)) + struct.pack(f"<{len(values)}d", *values)
+
+
+def string(value):
+    data = value.encode()
+    return struct.pack("<Q", len(data)) + data
+
+
+def v1_0():
+    fixed_width_axis = b"\x01" + struct.pack("<dddQ", 0.0, 2.0, 0.5, 4)
+    metadata = struct.pack("<Q", 1) + string("title") + string("Energy")
+    return (
+        MAGIC
+        + struct.pack("<HH", 1, 0)
+        + section(b"AXIS", fixed_width_axis)
+        + section(b"CONT", f64s([1.0, 0.0, 2.5, 1.0]))
+        + section(b"VARI", f64s([1.0, 0.0, 4.25, 1.0]))
+        + section(b"flow", f64s([1.0, 1.0, 2.0, 4.0]))
+        # Unknown ancillary section, skipped by readers
+        + section(b"note", b"written by make_histr_fixtures.py")
+        + section(b"meta", metadata)
+        + section(b"DONE", b"")
+    )
+
+
+if __name__ == "__main__":
+    with open("h1_v1.0.hst", "wb") as f:
+        f.write(v1_0())
@@ -0,0 +1,166 @@
+"""Generate the small ROOT files used in the tests of `histr::root`.
+
+The files follow the ROOT file layout (header, top directory, keys list,
+streamer info and free segments) so that no ROOT installation is needed.
+They share the layout assumptions of the Rust code and are not written by
+ROOT; the tests also open them with oxyroot, an independent reader. Files
+written by ROOT or uproot come from make_external_root_fixtures.py.
+
+Run from this directory: python make_root_fixtures.py
+"""
+
+import struct
+import zlib
+
+BEGIN = 100
+DATIME = (2024 - 1995) << 26 | 5 << 22 | 17 << 17 | 12 << 12
+UUID = struct.pack(">h", 1) + bytes(range(16))
+
+
+def tstring(value):
+    data = value.encode()
+    assert len(data) < 255
+    return bytes([len(data)]) + data
+
+
+def with_byte_count(version, payload):
+    payload = struct.pack(">h", version) + payload
+    return struct.pack(">I", len(payload) | 0x40000000) + payload
+
+
+def tobject():
+    return struct.pack(">hII", 1, 0, 0x03000000)
+
+
+def tnamed(name, title):
+    return with_byte_count(1, tobject() + tstring(name) + tstring(title))
+
+
+def tarray(fmt, values):
+    return struct.pack(f">i{len(values)}{fmt}", len(values), *values)
+
+
+def tlist():
+    return with_byte_count(5, tobject() + tstring("") + struct.pack(">i", 0))
+
+
+def taxis(name, nbins, xmin, xmax, edges=()):
+    att_axis = with_byte_count(
+        4, struct.pack(">ihhhfffffhh", 510, 1, 1, 42, 0.005, 0.035, 0.03, 1.0, 0.035, 1, 42)
+    )
+    payload = (
+        tnamed(name, "")
+        + att_axis
+        + struct.pack(">idd", nbins, xmin, xmax)
+        + tarray("d", edges)
+        + struct.pack(">iiHB", 0, 0, 0, 0)
+        + tstring("")
+        + struct.pack(">II", 0, 0)
+    )
+    return with_byte_count(10, payload)
+
+
+def th1(class_name, name, title, nbins, xmin, xmax, edges, contents, sumw2):
+    fmt = "d" if class_name == "TH1D" else "f"
+    th1 = (
+        tnamed(name, title)
+        + with_byte_count(2, struct.pack(">hhh", 602, 1, 1))
+        + with_byte_count(2, struct.pack(">hh", 0, 1001))
+        + with_byte_count(2, struct.pack(">hhf", 1, 1, 1.0))
+        + struct.pack(">i", nbins + 2)
+        + taxis("xaxis", nbins, xmin, xmax, edges)
+        + taxis("yaxis", 1, 0.0, 1.0)
+        + taxis("zaxis", 1, 0.0, 1.0)
+        + struct.pack(">hh", 0, 1000)
+        + struct.pack(">8d", sum(contents), sum(contents[1:-1]), 0, 0, 0, -1111, -1111, 0)
+        + tarray("d", ())
+        + tarray("d", sumw2)
+        + tstring("")
+        + tlist()
+        + struct.pack(">iBii", 0, 0, 0, 2)
+    )
+    return with_byte_count(3, with_byte_count(8, th1) + tarray(fmt, contents))
+
+
+def key(class_name, name, title, seek, obj_len, data_len, cycle=1):
+    names = tstring(class_name) + tstring(name) + tstring(title)
+    key_len = 26 + len(names)
+    header = struct.pack(
+        ">ihiIhhii", key_len + data_len, 4, obj_len, DATIME, key_len, cycle, seek, BEGIN
+    )
+    return header + names
+
+
+def compress(data):
+    compressed = zlib.compress(data)
+    header = b"ZL\x08" + len(compressed).to_bytes(3, "little") + len(data).to_bytes(3, "little")
+    return header + compressed
+
+
+def root_file(file_name, class_name, name, title, obj, compressed):
+    data = compress(obj) if compressed else obj
+    names = tstring(file_name) + tstring("")
+    dir_key_len = 26 + len(tstring("TFile")) + len(names)
+    nbytes_name = dir_key_len + len(names)
+    obj_seek = BEGIN + nbytes_name + 60
+
+    obj_record = key(class_name, name, title, obj_seek, len(obj), len(data)) + data
+    info_seek = obj_seek + len(obj_record)
+    info = tlist()
+    info_record = key("TList", "StreamerInfo", "Doubly linked list", info_seek, len(info), len(info)) + info
+    keys_seek = info_seek + len(info_record)
+    keys = struct.pack(">i", 1) + obj_record[: len(obj_record) - len(data)]
+    keys_record = key("TFile", file_name, "", keys_seek, len(keys), len(keys)) + keys
+    free_seek = keys_seek + len(keys_record)
+    free_key = key("TFile", file_name, "", free_seek, 10, 10)
+    end = free_seek + len(free_key) + 10
+    free_record = free_key + struct.pack(">hii", 1, end, 2000000000)
+
+    header = b"root" + struct.pack(
+        ">iiiiiiiBiii",
+        62804,
+        BEGIN,
+        end,
+        free_seek,
+        len(free_record),
+        1,
+        nbytes_name,
+        4,
+        101 if compressed else 0,
+        info_seek,
+        len(info_record),
+    ) + UUID
+    header = header.ljust(BEGIN, b"\0")
+
+    directory = (
+        key("TFile", file_name, "", BEGIN, len(names) + 60, len(names) + 60)
+        + names
+        + struct.pack(">hIIiiiii", 5, DATIME, DATIME, len(keys_record), nbytes_name, BEGIN, 0, keys_seek)
+        + UUID
+        + bytes(12)
+    )
+    return header + directory + obj_record + info_record + keys_record + free_record
+
+
+def main():
+    # Fixed bins [0, 2] in 4 bins with weights (sumw2) and both flow bins
+    obj = th1(
+        "TH1D", "h", "fixed", 4, 0.0, 2.0, (),
+        contents=[2.0, 1.0, 2.5, 0.0, 4.0, 0.5],
+        sumw2=[2.0, 1.0, 3.25, 0.0, 8.0, 0.25],
+    )
+    with open("th1d_fixed.root", "wb") as f:
+        f.write(root_file("th1d_fixed.root", "TH1D", "h", "fixed", obj, compressed=False))
+
+    # Variable bins, float contents, zlib compression, no sumw2
+    obj = th1(
+        "TH1F", "energy", "Energy", 3, 0.0, 100.0, (0.0, 1.0, 10.0, 100.0),
+        contents=[0.0, 3.0, 5.0, 1.0, 2.0],
+        sumw2=[],
+    )
+    with open("th1f_variable_zlib.root", "wb") as f:
+        f.write(root_file("th1f_variable_zlib.root", "TH1F", "energy", "Energy", obj, compressed=True))
+
+
+if __name__ == "__main__":
+    main()
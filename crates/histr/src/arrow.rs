@@ -0,0 +1,191 @@
+//! Apache Arrow / Parquet interoperability.
+//!
+//! Histograms can be filled directly from numeric Arrow arrays (null values
+//! are skipped) and exported as a record batch with one row per bin.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    ArrowPrimitiveType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+
+use crate::h1::H1;
+
+/// Convert a numeric array to `f64` values, with `None` for nulls.
+pub fn array_values(array: &dyn Array) -> Result<Vec<Option<f64>>, &'static str> {
+    fn convert<T: ArrowPrimitiveType>(
+        array: &dyn Array,
+        to_f64: impl Fn(T::Native) -> f64,
+    ) -> Vec<Option<f64>> {
+        array
+            .as_primitive::<T>()
+            .iter()
+            .map(|value| value.map(&to_f64))
+            .collect()
+    }
+
+    Ok(match array.data_type() {
+        DataType::Float64 => convert::<Float64Type>(array, |x| x),
+        DataType::Float32 => convert::<Float32Type>(array, f64::from),
+        DataType::Int8 => convert::<Int8Type>(array, f64::from),
+        DataType::Int16 => convert::<Int16Type>(array, f64::from),
+        DataType::Int32 => convert::<Int32Type>(array, f64::from),
+        DataType::Int64 => convert::<Int64Type>(array, |x| x as f64),
+        DataType::UInt8 => convert::<UInt8Type>(array, f64::from),
+        DataType::UInt16 => convert::<UInt16Type>(array, f64::from),
+        DataType::UInt32 => convert::<UInt32Type>(array, f64::from),
+        DataType::UInt64 => convert::<UInt64Type>(array, |x| x as f64),
+        _ => return Err("Unsupported Arrow data type."),
+    })
+}
+
+impl H1<'_> {
+    /// Fill values from a numeric Arrow array, skipping nulls.
+    pub fn fill_array(&mut self, values: &dyn Array) -> Result<(), &'static str> {
+        if values.null_count() == 0 {
+            if let Some(values) = values.as_primitive_opt::<Float64Type>() {
+                self.fill_many(values.values());
+                return Ok(());
+            }
+        }
+        let values: Vec<f64> = array_values(values)?.into_iter().flatten().collect();
+        self.fill_many(&values);
+        Ok(())
+    }
+
+    /// Fill weighted values from numeric Arrow arrays.
+    ///
+    /// Rows where either the value or the weight is null are skipped.
+    pub fn fill_weighted_array(
+        &mut self,
+        values: &dyn Array,
+        weights: &dyn Array,
+    ) -> Result<(), &'static str> {
+        if values.len() != weights.len() {
+            return Err("Data and weights must have the same length.");
+        }
+        let (values, weights): (Vec<f64>, Vec<f64>) = array_values(values)?
+            .into_iter()
+            .zip(array_values(weights)?)
+            .filter_map(|pair| match pair {
+                (Some(value), Some(weight)) => Some((value, weight)),
+                _ => None,
+            })
+            .unzip();
+        self.fill_weighted_many(&values, &weights)
+    }
+}
+
+/// Record batch with `lower`, `upper`, `content` and `variance` columns.
+///
+/// Histogram metadata become the schema metadata.
+pub fn to_record_batch(h1: &H1) -> RecordBatch {
+    let edges = h1.axis().bin_edges();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from(edges[..edges.len() - 1].to_vec())),
+        Arc::new(Float64Array::from(edges[1..].to_vec())),
+        Arc::new(Float64Array::from(h1.bin_contents().clone())),
+        Arc::new(Float64Array::from(h1.variances().into_owned())),
+    ];
+    let fields = ["lower", "upper", "content", "variance"]
+        .map(|name| Field::new(name, DataType::Float64, false));
+    let metadata: HashMap<String, String> = h1
+        .metadata()
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let schema = Schema::new(fields.to_vec()).with_metadata(metadata);
+    RecordBatch::try_new(Arc::new(schema), columns).expect("Columns match the schema.")
+}
+
+pub fn write_parquet(h1: &H1, writer: impl Write + Send) -> Result<(), Box<dyn Error>> {
+    let batch = to_record_batch(h1);
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::GeneralAxis;
+    use arrow_array::{Float32Array, Int32Array, UInt8Array};
+    use bytes::Bytes;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::error::Error;
+
+    fn get_h1() -> H1<'static> {
+        H1::new(
+            Box::new(GeneralAxis::new(vec![0., 1., 2., 4.])),
+            vec![0.0; 3],
+        )
+    }
+
+    #[test]
+    fn test_fill_array() -> Result<(), Box<dyn Error>> {
+        let mut h1 = get_h1();
+        h1.fill_array(&Float64Array::from(vec![0.5, 1.5, 7.0]))?;
+        h1.fill_array(&Float32Array::from(vec![Some(2.5), None, Some(-1.0)]))?;
+        h1.fill_array(&Int32Array::from(vec![Some(0), Some(3), None]))?;
+
+        assert_eq!(h1.bin_contents(), &vec![2.0, 1.0, 2.0]);
+        assert_eq!(h1.underflow().value, 1.0);
+        assert_eq!(h1.overflow().value, 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_weighted_array() -> Result<(), Box<dyn Error>> {
+        let mut h1 = get_h1();
+        let values = UInt8Array::from(vec![Some(0), Some(1), None, Some(3)]);
+        let weights = Float64Array::from(vec![Some(2.0), None, Some(1.0), Some(0.5)]);
+        h1.fill_weighted_array(&values, &weights)?;
+
+        assert_eq!(h1.bin_contents(), &vec![2.0, 0.0, 0.5]);
+        assert_eq!(*h1.variances(), vec![4.0, 0.0, 0.25]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_type() {
+        let strings = arrow_array::StringArray::from(vec!["a"]);
+        assert!(get_h1().fill_array(&strings).is_err());
+    }
+
+    #[test]
+    fn test_to_record_batch() {
+        let mut h1 = get_h1();
+        h1.fill_many(&[0.5, 3.0, 3.5]);
+        h1.metadata_mut().insert("unit".into(), "keV".into());
+        let batch = to_record_batch(&h1);
+
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().metadata()["unit"], "keV");
+        let column = |name| batch[name].as_primitive::<Float64Type>().values().to_vec();
+        assert_eq!(column("lower"), vec![0.0, 1.0, 2.0]);
+        assert_eq!(column("upper"), vec![1.0, 2.0, 4.0]);
+        assert_eq!(column("content"), vec![1.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_write_parquet() -> Result<(), Box<dyn Error>> {
+        let mut h1 = get_h1();
+        h1.fill_many(&[0.5, 3.0]);
+        let mut buffer = Vec::new();
+        write_parquet(&h1, &mut buffer)?;
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buffer))?.build()?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches, vec![to_record_batch(&h1)]);
+        Ok(())
+    }
+}
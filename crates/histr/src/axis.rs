@@ -1,7 +1,15 @@
+use std::any::Any;
 use std::borrow::Cow;
 use std::fmt::Debug;
 use std::iter::zip;
 
+use wide::{f64x4, CmpEq, CmpGe, CmpGt, CmpLt};
+
+use crate::fillable::Fillable;
+
+// Number of values converted to f64 at once when filling other types.
+const CONVERSION_CHUNK: usize = 1024;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct GeneralAxis {
     bin_edges: Vec<f64>,
@@ -15,7 +23,7 @@ pub struct FixedWidthAxis { min_edge: f64,
 }
 
 pub trait Axis: Debug + Send + Sync {
-    fn bin_edges(&self) -> Cow<Vec<f64>>;
+    fn bin_edges(&self) -> Cow<'_, Vec<f64>>;
 
     fn min_edge(&self) -> f64 {
         self.bin_edges()[0]
@@ -30,6 +38,10 @@ pub trait Axis: Debug + Send + Sync {
         self.bin_edges().len() - 1
     }
 
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     fn get_bin(&self, n: usize) -> Option<(f64, f64)> {
         if n >= self.len() {
             None
@@ -84,6 +96,9 @@ pub trait Axis: Debug + Send + Sync {
     }
 
     fn clone_box(&self) -> Box<dyn Axis>;
+
+    /// Access to the concrete axis type, e.g. for serialization.
+    fn as_any(&self) -> &dyn Any;
 }
 
 impl GeneralAxis {
@@ -102,6 +117,10 @@ impl FixedWidthAxis {
         }
     }
 
+    pub fn bin_width(&self) -> f64 {
+        self.bin_width
+    }
+
     pub fn create_from_min_and_bins(min_edge: f64, bin_width: f64, n_bins: usize) -> Self {
         Self {
             min_edge,
@@ -111,6 +130,73 @@ impl FixedWidthAxis {
         }
     }
 
+    /// Bin indices of four values at once, `n_bins` for values outside of the axis.
+    ///
+    /// Gives the same bins as `find_bin`, computed with SIMD instructions.
+    #[inline]
+    fn find_bins_x4(&self, values: [f64; 4]) -> [usize; 4] {
+        let n_bins = self.n_bins as f64;
+        let values = f64x4::from(values);
+        // Inside the axis the quotient is not negative, so truncation below is the floor.
+        let bins = (values - f64x4::splat(self.min_edge)) / f64x4::splat(self.bin_width);
+        let outside = values.cmp_lt(f64x4::splat(self.min_edge))
+            | values.cmp_gt(f64x4::splat(self.max_edge))
+            | values.is_nan()
+            | bins.cmp_ge(f64x4::splat(n_bins));
+        let bins = outside.blend(f64x4::splat(n_bins), bins);
+        let bins = values
+            .cmp_eq(f64x4::splat(self.max_edge))
+            .blend(f64x4::splat(n_bins - 1.0), bins);
+        bins.to_array().map(|bin| bin as usize)
+    }
+
+    // Adds weights to the bins of the values, with one extra bin for values outside.
+    fn scatter_add(&self, data: &[f64], weight: impl Fn(usize) -> f64) -> Vec<f64> {
+        let mut result = vec![0.0; self.n_bins + 1];
+        let chunks = data.chunks_exact(4);
+        let remainder = chunks.remainder();
+        for (i, chunk) in chunks.enumerate() {
+            let bins = self.find_bins_x4([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            for (j, bin) in bins.into_iter().enumerate() {
+                result[bin] += weight(4 * i + j);
+            }
+        }
+        let offset = data.len() - remainder.len();
+        for (j, value) in remainder.iter().enumerate() {
+            let bin = self.find_bin(*value).unwrap_or(self.n_bins);
+            result[bin] += weight(offset + j);
+        }
+        result.truncate(self.n_bins);
+        result
+    }
+
+    /// Minimum edge and bin width if both are integers, with no partial last bin.
+    fn integer_alignment(&self) -> Option<(i64, i64)> {
+        let is_integer = |x: f64| x.fract() == 0.0 && x.abs() < (1u64 << 53) as f64;
+        let aligned = is_integer(self.min_edge)
+            && is_integer(self.bin_width)
+            && self.bin_width >= 1.0
+            && self.max_edge == self.min_edge + self.bin_width * self.n_bins as f64;
+        aligned.then_some((self.min_edge as i64, self.bin_width as i64))
+    }
+
+    // Same bin as `find_bin` for an integer value, without floating-point division.
+    fn find_integer_bin(&self, value: i64, (min_edge, bin_width): (i64, i64)) -> Option<usize> {
+        let offset = value.checked_sub(min_edge)?;
+        if offset < 0 {
+            return None;
+        }
+        let bin = (offset / bin_width) as u64;
+        if bin < self.n_bins as u64 {
+            Some(bin as usize)
+        } else if bin == self.n_bins as u64 && offset % bin_width == 0 {
+            // value == max_edge
+            Some(self.n_bins - 1)
+        } else {
+            None
+        }
+    }
+
     pub fn create_from_range(min_edge: f64, max_edge: f64, bin_width: f64) -> Self {
         // TODO: Make sure 5.00000001 bins do not become 6
         Self {
@@ -123,9 +209,9 @@ impl FixedWidthAxis {
 }
 
 impl Axis for FixedWidthAxis {
-    fn bin_edges(&self) -> Cow<Vec<f64>> {
+    fn bin_edges(&self) -> Cow<'_, Vec<f64>> {
         let mut edges = vec![self.min_edge];
-        for i in 1..(self.n_bins as usize) {
+        for i in 1..self.n_bins {
             edges.push(self.min_edge + i as f64 * self.bin_width);
         }
         edges.push(self.max_edge);
@@ -140,12 +226,20 @@ impl Axis for FixedWidthAxis {
         self.max_edge
     }
     
+    fn len(&self) -> usize {
+        self.n_bins
+    }
+
     fn find_bin(&self, value: f64) -> Option<usize> {
-        if value < self.min_edge {
+        if value.is_nan() || value < self.min_edge {
+            return None;
+        }
+        // A partial last bin ends before min_edge + n_bins * bin_width
+        if value > self.max_edge {
             return None;
         }
         if value == self.max_edge {
-            return Some((self.n_bins - 1) as usize);
+            return Some(self.n_bins - 1);
         }
         let bin = ((value - self.min_edge) / self.bin_width).floor() as usize;
         if bin >= self.n_bins {
@@ -154,29 +248,110 @@ impl Axis for FixedWidthAxis {
         Some(bin)
     }
 
+    fn apply(&self, data: &[f64]) -> Vec<f64> {
+        self.scatter_add(data, |_| 1.0)
+    }
+
+    fn apply_weighted(&self, data: &[f64], weights: &[f64]) -> Result<Vec<f64>, &'static str> {
+        if data.len() != weights.len() {
+            return Err("Data and weights must have the same length.");
+        }
+        Ok(self.scatter_add(data, |i| weights[i]))
+    }
+
     fn clone_box(&self) -> Box<dyn Axis> {
-        return Box::new(FixedWidthAxis {
+        Box::new(FixedWidthAxis {
             min_edge: self.min_edge,
             n_bins: self.n_bins,
             max_edge: self.max_edge,
             bin_width: self.bin_width,
-        });
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl dyn Axis + '_ {
+    /// Same as `apply`, for values of any numeric type.
+    ///
+    /// Integer values on fixed-width axes with integer edges are binned exactly
+    /// without conversion to `f64`.
+    pub fn apply_values<T: Fillable>(&self, data: &[T]) -> Vec<f64> {
+        if let Some(data) = T::as_f64_slice(data) {
+            return self.apply(data);
+        }
+        let mut result = vec![0.0; self.len()];
+        let alignment = self
+            .as_any()
+            .downcast_ref::<FixedWidthAxis>()
+            .and_then(|axis| Some((axis, axis.integer_alignment()?)));
+        match alignment {
+            Some((axis, alignment)) if T::INTEGER => {
+                for value in data {
+                    let bin = match value.to_i64() {
+                        Some(value) => axis.find_integer_bin(value, alignment),
+                        None => axis.find_bin(value.to_f64()),
+                    };
+                    if let Some(bin) = bin {
+                        result[bin] += 1.0;
+                    }
+                }
+            }
+            _ => {
+                let mut buffer = [0.0; CONVERSION_CHUNK];
+                for chunk in data.chunks(CONVERSION_CHUNK) {
+                    let buffer = &mut buffer[..chunk.len()];
+                    zip(buffer.iter_mut(), chunk).for_each(|(x, value)| *x = value.to_f64());
+                    zip(result.iter_mut(), self.apply(buffer)).for_each(|(r, count)| *r += count);
+                }
+            }
+        }
+        result
+    }
+
+    /// Same as `apply_weighted`, for values of any numeric type.
+    pub fn apply_weighted_values<T: Fillable>(
+        &self,
+        data: &[T],
+        weights: &[f64],
+    ) -> Result<Vec<f64>, &'static str> {
+        if let Some(data) = T::as_f64_slice(data) {
+            return self.apply_weighted(data, weights);
+        }
+        if data.len() != weights.len() {
+            return Err("Data and weights must have the same length.");
+        }
+        let mut result = vec![0.0; self.len()];
+        let mut buffer = [0.0; CONVERSION_CHUNK];
+        for (chunk, weights) in zip(data.chunks(CONVERSION_CHUNK), weights.chunks(CONVERSION_CHUNK)) {
+            let buffer = &mut buffer[..chunk.len()];
+            zip(buffer.iter_mut(), chunk).for_each(|(x, value)| *x = value.to_f64());
+            let sums = self.apply_weighted(buffer, weights)?;
+            zip(result.iter_mut(), sums).for_each(|(r, sum)| *r += sum);
+        }
+        Ok(result)
     }
 }
 
 impl From<&[f64]> for Box<GeneralAxis> {
     fn from(value: &[f64]) -> Self {
-        return Box::new(GeneralAxis::new(value.to_vec()));
+        Box::new(GeneralAxis::new(value.to_vec()))
     }
 }
 
 impl Axis for GeneralAxis {
-    fn bin_edges(&self) -> Cow<Vec<f64>> {
+    fn bin_edges(&self) -> Cow<'_, Vec<f64>> {
         Cow::Borrowed(&self.bin_edges)
     }
 
     fn clone_box(&self) -> Box<dyn Axis> {
-        return Box::new(GeneralAxis::new(self.bin_edges.clone()));
+        Box::new(GeneralAxis::new(self.bin_edges.clone()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 }
 
@@ -201,4 +376,113 @@ mod tests {
             assert_eq!(ax.find_bin(3.2), None);
         }
     }
+
+    mod fixed_width {
+        use crate::axis::{Axis, FixedWidthAxis};
+
+        // Values around the edges, at the limits and not finite
+        fn get_values(axis: &FixedWidthAxis) -> Vec<f64> {
+            let mut values = vec![f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.0, 0.0];
+            values.extend([f64::MAX, f64::MIN, f64::MIN_POSITIVE, f64::EPSILON]);
+            for edge in axis.bin_edges().iter() {
+                let below = f64::from_bits(edge.to_bits() - 1);
+                let above = f64::from_bits(edge.to_bits() + 1);
+                values.extend([below, *edge, above]);
+            }
+            values.extend((0..1000).map(|i| i as f64 * 0.0137 - 2.0));
+            values
+        }
+
+        #[test]
+        fn test_find_bins_x4() {
+            let axes = [
+                FixedWidthAxis::create_from_min_and_bins(0.1, 0.1, 10),
+                FixedWidthAxis::create_from_min_and_bins(-1.0, 0.3, 7),
+                FixedWidthAxis::create_from_range(0.5, 2.25, 0.5),
+            ];
+            for axis in axes {
+                let values = get_values(&axis);
+                for chunk in values.chunks_exact(4) {
+                    let bins = axis.find_bins_x4([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                    for (value, bin) in chunk.iter().zip(bins) {
+                        let expected = axis.find_bin(*value).unwrap_or(axis.len());
+                        assert_eq!(bin, expected, "{value} in {axis:?}");
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_apply() {
+            let axis = FixedWidthAxis::create_from_min_and_bins(-1.0, 0.3, 7);
+            let values = get_values(&axis);
+            let weights: Vec<f64> = (0..values.len()).map(|i| (i % 5) as f64).collect();
+            let mut expected = vec![0.0; axis.len()];
+            let mut expected_weighted = vec![0.0; axis.len()];
+            for (value, weight) in values.iter().zip(&weights) {
+                if let Some(bin) = axis.find_bin(*value) {
+                    expected[bin] += 1.0;
+                    expected_weighted[bin] += weight;
+                }
+            }
+
+            assert_eq!(axis.apply(&values), expected);
+            assert_eq!(axis.apply_weighted(&values, &weights), Ok(expected_weighted));
+            assert!(axis.apply_weighted(&values, &weights[1..]).is_err());
+        }
+
+        #[test]
+        fn test_integer_values() {
+            let axes = [
+                FixedWidthAxis::create_from_min_and_bins(-3.0, 2.0, 5),
+                FixedWidthAxis::create_from_min_and_bins(0.5, 1.0, 4),
+                FixedWidthAxis::create_from_range(0.0, 9.0, 2.0),
+            ];
+            let values: Vec<i64> = (-10..20).chain([i64::MIN, i64::MAX]).collect();
+            let floats: Vec<f64> = values.iter().map(|value| *value as f64).collect();
+            for axis in axes {
+                let axis: &dyn Axis = &axis;
+                assert_eq!(axis.apply_values(&values), axis.apply(&floats), "{axis:?}");
+            }
+        }
+
+        #[test]
+        fn test_other_values() {
+            let axis: &dyn Axis = &FixedWidthAxis::create_from_min_and_bins(0.0, 0.5, 4);
+            let values: Vec<f32> = (0..3000).map(|i| i as f32 * 0.001).collect();
+            let floats: Vec<f64> = values.iter().map(|value| f64::from(*value)).collect();
+            let weights = vec![0.5; values.len()];
+
+            assert_eq!(axis.apply_values(&values), axis.apply(&floats));
+            assert_eq!(
+                axis.apply_weighted_values(&values, &weights),
+                axis.apply_weighted(&floats, &weights)
+            );
+            assert!(axis.apply_weighted_values(&values, &weights[1..]).is_err());
+            assert_eq!(axis.apply_values(&[1u8, 1, 2]), vec![0.0, 0.0, 2.0, 1.0]);
+        }
+
+        #[test]
+        fn test_partial_last_bin() {
+            // Edges -0.25, 0.25, 0.75, 1.0
+            let axis = FixedWidthAxis::new(-0.25, 1.0, 0.5, 3);
+            assert_eq!(axis.find_bin(0.9), Some(2));
+            assert_eq!(axis.find_bin(1.0), Some(2));
+            assert_eq!(axis.find_bin(1.1), None);
+            assert_eq!(axis.find_bin(1.25), None);
+            assert_eq!(axis.find_bins_x4([0.9, 1.0, 1.1, 1.25]), [2, 2, 3, 3]);
+            assert_eq!(axis.apply(&[0.9, 1.0, 1.1, 1.2, 1.25]), vec![0.0, 0.0, 2.0]);
+
+            // Integers are not binned exactly without a whole last bin
+            let axis: &dyn Axis = &FixedWidthAxis::new(0.0, 5.0, 2.0, 3);
+            assert_eq!(axis.apply_values(&[4i32, 5, 6]), vec![0.0, 0.0, 2.0]);
+        }
+
+        #[test]
+        fn test_nan() {
+            let axis = FixedWidthAxis::create_from_min_and_bins(0.0, 1.0, 2);
+            assert_eq!(axis.find_bin(f64::NAN), None);
+            assert_eq!(axis.apply(&[f64::NAN; 5]), vec![0.0, 0.0]);
+        }
+    }
 }
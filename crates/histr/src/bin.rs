@@ -1,12 +1,12 @@
-// TODO: parameterize types
+/// Bin of a histogram with its edges, the value is of the histogram storage type.
 #[derive(Debug, PartialEq)]
-pub struct Bin {
-    pub value: f64,
+pub struct Bin<S = f64> {
+    pub value: S,
     pub lower: f64,
     pub upper: f64,
 }
 
-impl Bin {
+impl<S> Bin<S> {
     pub fn width(&self) -> f64 {
         self.upper - self.lower
     }
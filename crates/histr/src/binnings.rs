@@ -1,9 +1,26 @@
 use crate::axis::{Axis, FixedWidthAxis};
+use std::str::FromStr;
 
 pub trait BinningAlgorithm {
     type AxisType: Axis + 'static;
 
     fn find_axis(&self, data: &[f64]) -> Result<Box<Self::AxisType>, &str>;
+
+    /// Find the axis for an explicit range, ignoring data outside it.
+    fn find_axis_in_range(
+        &self,
+        data: &[f64],
+        range: (f64, f64),
+    ) -> Result<Box<Self::AxisType>, &str> {
+        check_range(range)?;
+        let data: Vec<f64> = data
+            .iter()
+            .copied()
+            .filter(|value| *value >= range.0 && *value <= range.1)
+            .collect();
+        self.find_axis(&data)
+            .map_err(|_| "Cannot find axis for the data in the range.")
+    }
 }
 
 /// StandardBins is a simple binning algorithm that splits the data into n_bins
@@ -39,7 +56,19 @@ fn find_bounds(data: &[f64]) -> Result<(f64, f64), &'static str> {
     Ok((min, max))
 }
 
+fn check_range(range: (f64, f64)) -> Result<(), &'static str> {
+    let (min, max) = range;
+    if !min.is_finite() || !max.is_finite() {
+        return Err("Range must be finite.");
+    }
+    if min >= max {
+        return Err("Range minimum must be smaller than its maximum.");
+    }
+    Ok(())
+}
+
 impl StandardBins {
+    #[allow(dead_code)]
     fn split_interval(&self, min: f64, max: f64) -> Result<Vec<f64>, &'static str> {
         if min.is_infinite() {
             return Err("Infinite values in the data");
@@ -70,7 +99,7 @@ impl StandardBins {
 impl BinningAlgorithm for StandardBins {
     type AxisType = FixedWidthAxis;
 
-    fn find_axis<'a>(&self, data: &'a [f64]) -> Result<Box<FixedWidthAxis>, &'static str> {
+    fn find_axis(&self, data: &[f64]) -> Result<Box<FixedWidthAxis>, &'static str> {
         let (min, max) = find_bounds(data)?;
         let bin_width = (max - min) / self.n_bins as f64;
         Ok(Box::new(FixedWidthAxis::new(
@@ -80,6 +109,17 @@ impl BinningAlgorithm for StandardBins {
             self.n_bins
         )))
     }
+
+    fn find_axis_in_range(
+        &self,
+        _data: &[f64],
+        range: (f64, f64),
+    ) -> Result<Box<FixedWidthAxis>, &'static str> {
+        check_range(range)?;
+        let (min, max) = range;
+        let bin_width = (max - min) / self.n_bins as f64;
+        Ok(Box::new(FixedWidthAxis::new(min, max, bin_width, self.n_bins)))
+    }
 }
 
 pub struct FixedWidthBins {
@@ -97,6 +137,15 @@ impl BinningAlgorithm for FixedWidthBins {
             self.bin_width,
         )))
     }
+
+    fn find_axis_in_range(
+        &self,
+        _data: &[f64],
+        range: (f64, f64),
+    ) -> Result<Box<FixedWidthAxis>, &'static str> {
+        check_range(range)?;
+        Ok(Box::new(fixed_width_bins_in_range(range, self.bin_width)))
+    }
 }
 
 fn find_fixed_width_bins(min: f64, max: f64, bin_width: f64) -> FixedWidthAxis {
@@ -111,6 +160,15 @@ fn find_fixed_width_bins(min: f64, max: f64, bin_width: f64) -> FixedWidthAxis {
     )
 }
 
+// Bins of the given width starting at the minimum of an explicit range, which
+// is kept unchanged; the last bin is narrower if the width does not fit.
+fn fixed_width_bins_in_range(range: (f64, f64), bin_width: f64) -> FixedWidthAxis {
+    let (min, max) = range;
+    // Without an extra bin for rounding errors of a whole number of bins
+    let n_bins = ((max - min) / bin_width * (1.0 - 1e-12)).ceil().max(1.0) as usize;
+    FixedWidthAxis::new(min, max, bin_width, n_bins)
+}
+
 pub struct PrettyBins {
     pub approx_bins: usize,
 }
@@ -128,9 +186,136 @@ impl BinningAlgorithm for PrettyBins {
             bin_width,
         )))
     }
+
+    fn find_axis_in_range(
+        &self,
+        _data: &[f64],
+        range: (f64, f64),
+    ) -> Result<Box<FixedWidthAxis>, &'static str> {
+        check_range(range)?;
+        let (min, max) = range;
+        let bin_width = find_pretty_width((max - min) / (self.approx_bins - 1) as f64);
+        Ok(Box::new(fixed_width_bins_in_range(range, bin_width)))
+    }
+}
+
+/// Rules for the number of bins based on the data, as in numpy.histogram_bin_edges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinningRule {
+    Sqrt,
+    Sturges,
+    Rice,
+    Scott,
+    FreedmanDiaconis,
+    Doane,
+}
+
+impl FromStr for BinningRule {
+    type Err = &'static str;
+
+    fn from_str(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "sqrt" => Ok(BinningRule::Sqrt),
+            "sturges" => Ok(BinningRule::Sturges),
+            "rice" => Ok(BinningRule::Rice),
+            "scott" => Ok(BinningRule::Scott),
+            "fd" => Ok(BinningRule::FreedmanDiaconis),
+            "doane" => Ok(BinningRule::Doane),
+            _ => Err("Unknown binning rule."),
+        }
+    }
+}
+
+impl BinningRule {
+    /// Number of bins for data spanning (min, max).
+    pub fn n_bins(&self, data: &[f64], min: f64, max: f64) -> usize {
+        let n = data.len() as f64;
+        if data.len() < 2 || min == max {
+            return 1;
+        }
+        let from_width = |width: f64| {
+            if width > 0.0 {
+                ((max - min) / width).ceil().max(1.0) as usize
+            } else {
+                1
+            }
+        };
+        match self {
+            BinningRule::Sqrt => n.sqrt().ceil() as usize,
+            BinningRule::Sturges => n.log2().ceil() as usize + 1,
+            BinningRule::Rice => (2.0 * n.cbrt()).ceil() as usize,
+            BinningRule::Scott => {
+                let std = moments(data).1.sqrt();
+                from_width((24.0 * std::f64::consts::PI.sqrt() / n).cbrt() * std)
+            }
+            BinningRule::FreedmanDiaconis => {
+                let mut sorted = data.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let iqr = percentile(&sorted, 75.0) - percentile(&sorted, 25.0);
+                from_width(2.0 * iqr / n.cbrt())
+            }
+            BinningRule::Doane => {
+                if data.len() < 3 {
+                    return 1;
+                }
+                let (mean, variance) = moments(data);
+                let std = variance.sqrt();
+                if std == 0.0 {
+                    return 1;
+                }
+                let g1 = data.iter().map(|x| ((x - mean) / std).powi(3)).sum::<f64>() / n;
+                let sigma_g1 = (6.0 * (n - 2.0) / ((n + 1.0) * (n + 3.0))).sqrt();
+                (1.0 + n.log2() + (1.0 + g1.abs() / sigma_g1).log2()).ceil() as usize
+            }
+        }
+    }
+}
+
+impl BinningAlgorithm for BinningRule {
+    type AxisType = FixedWidthAxis;
+
+    fn find_axis(&self, data: &[f64]) -> Result<Box<FixedWidthAxis>, &'static str> {
+        let (min, max) = find_bounds(data)?;
+        let n_bins = self.n_bins(data, min, max);
+        let bin_width = (max - min) / n_bins as f64;
+        Ok(Box::new(FixedWidthAxis::new(min, max, bin_width, n_bins)))
+    }
+
+    fn find_axis_in_range(
+        &self,
+        data: &[f64],
+        range: (f64, f64),
+    ) -> Result<Box<FixedWidthAxis>, &'static str> {
+        check_range(range)?;
+        let data: Vec<f64> = data
+            .iter()
+            .copied()
+            .filter(|value| *value >= range.0 && *value <= range.1)
+            .collect();
+        let (min, max) = range;
+        let n_bins = self.n_bins(&data, min, max);
+        let bin_width = (max - min) / n_bins as f64;
+        Ok(Box::new(FixedWidthAxis::new(min, max, bin_width, n_bins)))
+    }
+}
+
+// Mean and (population) variance
+fn moments(data: &[f64]) -> (f64, f64) {
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance)
+}
+
+// Percentile with linear interpolation of sorted data
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    let position = q / 100.0 * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
 }
 
-fn find_pretty_width(raw_width: f64) -> f64 {
+pub(crate) fn find_pretty_width(raw_width: f64) -> f64 {
     let subscales = [0.5, 1., 2., 2.5, 5., 10.];
     let power = raw_width.log10().floor() as i32;
     let normalized_subscale: f64 = raw_width / (10.0f64).powi(power);
@@ -167,6 +352,86 @@ mod tests {
         }
     }
 
+    mod range {
+        use crate::axis::Axis;
+        use crate::binnings::{BinningAlgorithm, FixedWidthBins, PrettyBins, StandardBins};
+        use std::error::Error;
+
+        #[test]
+        fn standard_bins() -> Result<(), Box<dyn Error>> {
+            let algo = StandardBins { n_bins: 4 };
+            let axis = algo.find_axis_in_range(&[0.3, 0.5], (0.0, 2.0))?;
+            assert_eq!(vec![0.0, 0.5, 1.0, 1.5, 2.0], *axis.bin_edges());
+            Ok(())
+        }
+
+        #[test]
+        fn fixed_width_bins() -> Result<(), Box<dyn Error>> {
+            let algo = FixedWidthBins { bin_width: 0.5 };
+            let axis = algo.find_axis_in_range(&[], (0.0, 1.5))?;
+            assert_eq!(vec![0.0, 0.5, 1.0, 1.5], *axis.bin_edges());
+
+            // Not on the grid of the width: the range is kept, the last bin is narrower
+            let axis = algo.find_axis_in_range(&[], (-0.25, 1.0))?;
+            assert_eq!(vec![-0.25, 0.25, 0.75, 1.0], *axis.bin_edges());
+
+            // Three bins of 0.4 despite rounding
+            let algo = FixedWidthBins { bin_width: 0.4 };
+            let axis = algo.find_axis_in_range(&[], (-0.2, 1.0))?;
+            assert_eq!(axis.len(), 3);
+            assert_eq!((axis.min_edge(), axis.max_edge()), (-0.2, 1.0));
+            Ok(())
+        }
+
+        #[test]
+        fn pretty_bins() -> Result<(), Box<dyn Error>> {
+            let algo = PrettyBins { approx_bins: 4 };
+            let axis = algo.find_axis_in_range(&[0.5], (0.125, 1.0))?;
+            assert_eq!(vec![0.125, 0.375, 0.625, 0.875, 1.0], *axis.bin_edges());
+            Ok(())
+        }
+
+        #[test]
+        fn invalid_range() {
+            let algo = StandardBins { n_bins: 4 };
+            assert!(algo.find_axis_in_range(&[], (1.0, 0.0)).is_err());
+            assert!(algo.find_axis_in_range(&[], (0.0, f64::INFINITY)).is_err());
+        }
+    }
+
+    mod rules {
+        use crate::axis::Axis;
+        use crate::binnings::{BinningAlgorithm, BinningRule};
+        use std::error::Error;
+
+        #[test]
+        fn n_bins() {
+            // Numbers of bins following the estimators of numpy.histogram_bin_edges
+            let data: Vec<f64> = (0..100).map(|i| (i as f64).powi(2) / 100.0).collect();
+            let n_bins = |rule: BinningRule| rule.n_bins(&data, 0.0, 98.01);
+            assert_eq!(n_bins(BinningRule::Sqrt), 10);
+            assert_eq!(n_bins(BinningRule::Sturges), 8);
+            assert_eq!(n_bins(BinningRule::Rice), 10);
+            assert_eq!(n_bins(BinningRule::Scott), 5);
+            assert_eq!(n_bins(BinningRule::FreedmanDiaconis), 5);
+            assert_eq!(n_bins(BinningRule::Doane), 10);
+        }
+
+        #[test]
+        fn from_str() {
+            assert_eq!("fd".parse(), Ok(BinningRule::FreedmanDiaconis));
+            assert!("unknown".parse::<BinningRule>().is_err());
+        }
+
+        #[test]
+        fn find_axis_in_range() -> Result<(), Box<dyn Error>> {
+            let data = [0.5, 1.5, 2.5, 3.5, 100.0];
+            let axis = BinningRule::Sqrt.find_axis_in_range(&data, (0.0, 4.0))?;
+            assert_eq!(vec![0.0, 2.0, 4.0], *axis.bin_edges());
+            Ok(())
+        }
+    }
+
     mod pretty_bins {
         use crate::axis::{Axis};
         use crate::binnings::{find_pretty_width, BinningAlgorithm, PrettyBins};
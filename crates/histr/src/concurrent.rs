@@ -0,0 +1,230 @@
+//! Histogram that can be filled from many threads through a shared reference.
+//!
+//! Unit-weight fills are counted with atomic integers, weighted fills are
+//! accumulated in atomic `f64` values (updated in compare-and-swap loops).
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::axis::Axis;
+use crate::h1::{Flow, H1};
+
+// Adds to an f64 stored as bits in an AtomicU64.
+fn atomic_add(target: &AtomicU64, value: f64) {
+    let mut current = target.load(Ordering::Relaxed);
+    loop {
+        let new = (f64::from_bits(current) + value).to_bits();
+        match target.compare_exchange_weak(current, new, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+fn atomic_load(target: &AtomicU64) -> f64 {
+    f64::from_bits(target.load(Ordering::Relaxed))
+}
+
+#[derive(Debug, Default)]
+struct AtomicBin {
+    count: AtomicU64,
+    // Sums of weights and of squared weights, stored as f64 bits (zero bits are 0.0)
+    sum: AtomicU64,
+    sum2: AtomicU64,
+}
+
+impl AtomicBin {
+    fn fill(&self) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn fill_weighted(&self, weight: f64) {
+        atomic_add(&self.sum, weight);
+        atomic_add(&self.sum2, weight * weight);
+    }
+
+    fn value(&self) -> f64 {
+        self.count.load(Ordering::Relaxed) as f64 + atomic_load(&self.sum)
+    }
+
+    fn variance(&self) -> f64 {
+        self.count.load(Ordering::Relaxed) as f64 + atomic_load(&self.sum2)
+    }
+}
+
+/// One-dimensional histogram with atomic storage.
+///
+/// All fill methods take `&self`, so it can be shared between threads (e.g.
+/// with `std::thread::scope` or in an `Arc`). Use [`ConcurrentH1::snapshot`]
+/// to get an ordinary [`H1`].
+#[derive(Debug)]
+pub struct ConcurrentH1<'a> {
+    axis: Box<dyn Axis + 'a>,
+    bins: Vec<AtomicBin>,
+    underflow: AtomicBin,
+    overflow: AtomicBin,
+    weighted: AtomicBool,
+}
+
+impl<'a> ConcurrentH1<'a> {
+    pub fn new(axis: Box<dyn Axis + 'a>) -> Self {
+        let bins = (0..axis.len()).map(|_| AtomicBin::default()).collect();
+        ConcurrentH1 {
+            axis,
+            bins,
+            underflow: AtomicBin::default(),
+            overflow: AtomicBin::default(),
+            weighted: AtomicBool::new(false),
+        }
+    }
+
+    pub fn axis(&self) -> &dyn Axis {
+        self.axis.as_ref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+
+    // Bin for the value, flow bins outside of the axis, None for NaN.
+    fn find_bin(&self, value: f64) -> Option<&AtomicBin> {
+        match self.axis.find_bin(value) {
+            Some(bin) => Some(&self.bins[bin]),
+            None if value < self.axis.min_edge() => Some(&self.underflow),
+            None if value > self.axis.max_edge() => Some(&self.overflow),
+            None => None,
+        }
+    }
+
+    pub fn fill(&self, value: f64) {
+        if let Some(bin) = self.find_bin(value) {
+            bin.fill();
+        }
+    }
+
+    pub fn fill_weighted(&self, value: f64, weight: f64) {
+        self.weighted.store(true, Ordering::Relaxed);
+        if let Some(bin) = self.find_bin(value) {
+            bin.fill_weighted(weight);
+        }
+    }
+
+    pub fn fill_many(&self, values: &[f64]) {
+        values.iter().for_each(|value| self.fill(*value));
+    }
+
+    pub fn fill_weighted_many(&self, values: &[f64], weights: &[f64]) -> Result<(), &'static str> {
+        if values.len() != weights.len() {
+            return Err("Data and weights must have the same length.");
+        }
+        values
+            .iter()
+            .zip(weights)
+            .for_each(|(value, weight)| self.fill_weighted(*value, *weight));
+        Ok(())
+    }
+
+    /// Copy of the current state as an ordinary histogram.
+    ///
+    /// Bins are read one after the other, so fills running concurrently may
+    /// be included only partially.
+    pub fn snapshot(&self) -> H1<'static> {
+        let contents = self.bins.iter().map(AtomicBin::value).collect();
+        let mut h1 = H1::new(self.axis.clone_box(), contents);
+        if self.weighted.load(Ordering::Relaxed) {
+            let variances = self.bins.iter().map(AtomicBin::variance).collect();
+            h1.set_variances(Some(variances))
+                .expect("One variance per bin.");
+        }
+        let flow = |bin: &AtomicBin| Flow {
+            value: bin.value(),
+            variance: bin.variance(),
+        };
+        h1.set_underflow(flow(&self.underflow));
+        h1.set_overflow(flow(&self.overflow));
+        h1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::GeneralAxis;
+    use std::error::Error;
+    use std::thread;
+
+    fn get_axis() -> Box<GeneralAxis> {
+        Box::new(GeneralAxis::new(vec![0., 1., 2., 4.]))
+    }
+
+    fn get_values(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| ((i * 7919) % 1000) as f64 / 200.0 - 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_fill_from_threads() {
+        let values = get_values(40_000);
+        let h1 = ConcurrentH1::new(get_axis());
+        thread::scope(|scope| {
+            for chunk in values.chunks(10_000) {
+                let h1 = &h1;
+                scope.spawn(move || chunk.iter().for_each(|value| h1.fill(*value)));
+            }
+        });
+
+        let mut serial = H1::new(get_axis(), vec![0.0; 3]);
+        serial.fill_many(&values);
+        let snapshot = h1.snapshot();
+        assert_eq!(snapshot, serial);
+        assert!(!snapshot.has_variances());
+    }
+
+    #[test]
+    fn test_fill_weighted_from_threads() -> Result<(), Box<dyn Error>> {
+        let values = get_values(40_000);
+        let weights: Vec<f64> = (0..values.len()).map(|i| (i % 4) as f64 * 0.25).collect();
+        let h1 = ConcurrentH1::new(get_axis());
+        thread::scope(|scope| {
+            for (values, weights) in values.chunks(10_000).zip(weights.chunks(10_000)) {
+                let h1 = &h1;
+                scope.spawn(move || h1.fill_weighted_many(values, weights));
+            }
+        });
+        h1.fill(0.5);
+
+        let mut serial = H1::new(get_axis(), vec![0.0; 3]);
+        serial.fill_weighted_many(&values, &weights)?;
+        serial.fill(0.5);
+        assert!(h1.snapshot().approx_eq(&serial, 1e-12, 0.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_flow() {
+        let h1 = ConcurrentH1::new(get_axis());
+        h1.fill_many(&[-1.0, 5.0, 6.0, f64::NAN]);
+        h1.fill_weighted(7.0, 2.0);
+
+        let snapshot = h1.snapshot();
+        assert_eq!(snapshot.total(), 0.0);
+        assert_eq!(
+            snapshot.underflow(),
+            Flow {
+                value: 1.0,
+                variance: 1.0
+            }
+        );
+        assert_eq!(
+            snapshot.overflow(),
+            Flow {
+                value: 4.0,
+                variance: 6.0
+            }
+        );
+    }
+}
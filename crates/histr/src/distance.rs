@@ -0,0 +1,206 @@
+//! Distances and divergences between the distributions of two histograms.
+//!
+//! The histograms must have equal bins and are normalized to unit total
+//! before comparing, flow bins are not included. Divergences use the natural
+//! logarithm.
+
+use crate::h1::H1;
+use crate::stats::check_axes;
+use crate::storage::Storage;
+
+// Normalized bin contents of two histograms with equal bins.
+fn normalized<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<(Vec<f64>, Vec<f64>), &'static str> {
+    check_axes(first.axis(), second.axis())?;
+    Ok((normalize(&first.values())?, normalize(&second.values())?))
+}
+
+fn normalize(values: &[f64]) -> Result<Vec<f64>, &'static str> {
+    if values.iter().any(|value| *value < 0.0) {
+        return Err("Bin contents must not be negative.");
+    }
+    let total: f64 = values.iter().sum();
+    if total == 0.0 {
+        return Err("Histograms must not be empty.");
+    }
+    Ok(values.iter().map(|value| value / total).collect())
+}
+
+// Sum of p ln(p/q), infinite if q is zero where p is not.
+fn relative_entropy(p: &[f64], q: &[f64]) -> f64 {
+    p.iter()
+        .zip(q)
+        .filter(|(p_i, _)| **p_i > 0.0)
+        .map(|(p_i, q_i)| p_i * (p_i / q_i).ln())
+        .sum()
+}
+
+// Sum of sqrt(p q).
+fn bhattacharyya_coefficient(p: &[f64], q: &[f64]) -> f64 {
+    p.iter()
+        .zip(q)
+        .map(|(p_i, q_i)| (p_i * q_i).sqrt())
+        .sum::<f64>()
+        .min(1.0)
+}
+
+/// Kullback–Leibler divergence of the second distribution from the first.
+///
+/// Infinite if a bin is empty in the second histogram but not in the first.
+pub fn kl_divergence<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<f64, &'static str> {
+    let (p, q) = normalized(first, second)?;
+    Ok(relative_entropy(&p, &q))
+}
+
+/// Jensen–Shannon divergence, symmetric and bounded by ln 2.
+pub fn js_divergence<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<f64, &'static str> {
+    let (p, q) = normalized(first, second)?;
+    let m: Vec<f64> = p
+        .iter()
+        .zip(&q)
+        .map(|(p_i, q_i)| (p_i + q_i) / 2.0)
+        .collect();
+    Ok(((relative_entropy(&p, &m) + relative_entropy(&q, &m)) / 2.0).max(0.0))
+}
+
+/// Hellinger distance, sqrt(1 - BC) with the Bhattacharyya coefficient BC, in [0, 1].
+pub fn hellinger_distance<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<f64, &'static str> {
+    let (p, q) = normalized(first, second)?;
+    Ok((1.0 - bhattacharyya_coefficient(&p, &q)).sqrt())
+}
+
+/// Bhattacharyya distance, -ln BC, infinite for distributions without overlap.
+pub fn bhattacharyya_distance<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<f64, &'static str> {
+    let (p, q) = normalized(first, second)?;
+    Ok(-bhattacharyya_coefficient(&p, &q).ln())
+}
+
+/// Wasserstein-1 (earth mover's) distance, in units of the axis.
+///
+/// The contents of each bin are placed at its center, so the mass moved
+/// between neighbouring bins travels the distance between their centers,
+/// i.e. the mean of their widths.
+pub fn wasserstein_distance<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<f64, &'static str> {
+    let (p, q) = normalized(first, second)?;
+    let edges = first.axis().bin_edges();
+    let mut difference = 0.0;
+    let mut distance = 0.0;
+    for i in 0..p.len() - 1 {
+        difference += p[i] - q[i];
+        let center_distance = (edges[i + 2] - edges[i]) / 2.0;
+        distance += difference.abs() * center_distance;
+    }
+    Ok(distance)
+}
+
+/// Histogram intersection, the sum of the bin-wise minima, in [0, 1].
+pub fn intersection<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<f64, &'static str> {
+    let (p, q) = normalized(first, second)?;
+    Ok(p.iter().zip(&q).map(|(p_i, q_i)| p_i.min(*q_i)).sum())
+}
+
+/// Total variation distance, half the sum of absolute differences, in [0, 1].
+pub fn total_variation<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<f64, &'static str> {
+    let (p, q) = normalized(first, second)?;
+    Ok(p.iter()
+        .zip(&q)
+        .map(|(p_i, q_i)| (p_i - q_i).abs())
+        .sum::<f64>()
+        / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::{FixedWidthAxis, GeneralAxis};
+
+    fn h1(values: &[f64]) -> H1<'static> {
+        let n = values.len();
+        H1::new(
+            Box::new(FixedWidthAxis::new(0.0, n as f64, 1.0, n)),
+            values.to_vec(),
+        )
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-12 * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    // Reference values from mpmath
+    #[test]
+    fn test_divergences() {
+        let (p, q) = (h1(&[1.0, 2.0, 3.0, 4.0]), h1(&[4.0, 3.0, 2.0, 1.0]));
+        assert_close(kl_divergence(&p, &q).unwrap(), 0.456_434_819_146_783_6);
+        assert_close(js_divergence(&p, &q).unwrap(), 0.106_440_135_286_223_15);
+        assert_close(hellinger_distance(&p, &q).unwrap(), 0.331_816_291_708_777_3);
+        assert_close(
+            bhattacharyya_distance(&p, &q).unwrap(),
+            0.116_648_487_373_538_88,
+        );
+        assert_close(intersection(&p, &q).unwrap(), 0.6);
+        assert_close(total_variation(&p, &q).unwrap(), 0.4);
+
+        // Independent of the normalization
+        let scaled = (&q * 10.0).unwrap();
+        assert_close(kl_divergence(&p, &scaled).unwrap(), 0.456_434_819_146_783_6);
+        assert_eq!(kl_divergence(&p, &p).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_disjoint() {
+        let (p, q) = (h1(&[1.0, 0.0]), h1(&[0.0, 1.0]));
+        assert_eq!(kl_divergence(&p, &q).unwrap(), f64::INFINITY);
+        assert_close(js_divergence(&p, &q).unwrap(), 2f64.ln());
+        assert_eq!(hellinger_distance(&p, &q).unwrap(), 1.0);
+        assert_eq!(bhattacharyya_distance(&p, &q).unwrap(), f64::INFINITY);
+        assert_eq!(intersection(&p, &q).unwrap(), 0.0);
+        assert_eq!(total_variation(&p, &q).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_wasserstein_distance() {
+        // All the mass moves from the first to the last bin center
+        let axis = GeneralAxis::new(vec![0.0, 1.0, 3.0, 7.0]);
+        let p = H1::new(Box::new(axis.clone()), vec![2.0, 0.0, 0.0]);
+        let q = H1::new(Box::new(axis), vec![0.0, 0.0, 5.0]);
+        assert_close(wasserstein_distance(&p, &q).unwrap(), 4.5);
+
+        assert_close(
+            wasserstein_distance(&h1(&[1.0, 2.0, 3.0, 4.0]), &h1(&[4.0, 3.0, 2.0, 1.0])).unwrap(),
+            1.0,
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(kl_divergence(&h1(&[1.0, 2.0]), &h1(&[1.0, 2.0, 3.0])).is_err());
+        assert!(total_variation(&h1(&[0.0, 0.0]), &h1(&[1.0, 2.0])).is_err());
+        assert!(intersection(&h1(&[-1.0, 2.0]), &h1(&[1.0, 2.0])).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! Numeric types that histograms can be filled with.
+
+use std::borrow::Cow;
+
+/// Value that can be filled into a histogram.
+///
+/// Implemented for all primitive integer and floating-point types, so data do
+/// not have to be converted into a `Vec<f64>` first.
+pub trait Fillable: Copy + Send + Sync {
+    /// Whether the type holds integers (enables exact integer bin lookup).
+    const INTEGER: bool = false;
+
+    fn to_f64(self) -> f64;
+
+    /// The value as `i64`, `None` for floats and integers out of its range.
+    fn to_i64(self) -> Option<i64> {
+        None
+    }
+
+    /// The values themselves if they are already `f64`.
+    fn as_f64_slice(_values: &[Self]) -> Option<&[f64]> {
+        None
+    }
+}
+
+impl Fillable for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn as_f64_slice(values: &[Self]) -> Option<&[f64]> {
+        Some(values)
+    }
+}
+
+impl Fillable for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+macro_rules! impl_fillable_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Fillable for $t {
+                const INTEGER: bool = true;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn to_i64(self) -> Option<i64> {
+                    i64::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_fillable_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Values as `f64`, borrowed when no conversion is needed.
+pub fn to_f64_values<T: Fillable>(values: &[T]) -> Cow<'_, [f64]> {
+    match T::as_f64_slice(values) {
+        Some(values) => Cow::Borrowed(values),
+        None => Cow::Owned(values.iter().map(|value| value.to_f64()).collect()),
+    }
+}
@@ -0,0 +1,670 @@
+//! Fitting of parametric models to histograms.
+//!
+//! Models are densities in entries per unit of x: the expected content of a
+//! bin is the model at the bin center times the bin width or, in integral
+//! mode, the integral of the model over the bin. Flow bins are not included.
+//!
+//! The cost is minimized with the Levenberg–Marquardt algorithm, with the
+//! expected Hessian (Fisher scoring) and derivatives by finite differences.
+
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+use crate::h1::H1;
+use crate::special::{chi2_sf, erf};
+use crate::storage::Storage;
+
+/// Parametric model, with a blanket implementation for closures `|x, params| ...`.
+pub trait Model {
+    /// Value at `x`, in entries per unit of x.
+    fn evaluate(&self, x: f64, params: &[f64]) -> f64;
+
+    /// Number of parameters, `None` if the model takes any number.
+    fn n_params(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<F: Fn(f64, &[f64]) -> f64> Model for F {
+    fn evaluate(&self, x: f64, params: &[f64]) -> f64 {
+        self(x, params)
+    }
+}
+
+/// Gaussian with parameters [norm, mean, sigma], `norm` being its area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gaussian;
+
+impl Model for Gaussian {
+    fn evaluate(&self, x: f64, params: &[f64]) -> f64 {
+        let [norm, mean, sigma] = params[..] else {
+            return f64::NAN;
+        };
+        if sigma <= 0.0 {
+            return f64::NAN;
+        }
+        let t = (x - mean) / sigma;
+        norm / (sigma * (2.0 * PI).sqrt()) * (-t * t / 2.0).exp()
+    }
+
+    fn n_params(&self) -> Option<usize> {
+        Some(3)
+    }
+}
+
+/// Exponential norm · exp(slope · x) with parameters [norm, slope].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exponential;
+
+impl Model for Exponential {
+    fn evaluate(&self, x: f64, params: &[f64]) -> f64 {
+        let [norm, slope] = params[..] else {
+            return f64::NAN;
+        };
+        norm * (slope * x).exp()
+    }
+
+    fn n_params(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+/// Polynomial c0 + c1 x + ... with the `degree + 1` coefficients as parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Polynomial {
+    pub degree: usize,
+}
+
+impl Model for Polynomial {
+    fn evaluate(&self, x: f64, params: &[f64]) -> f64 {
+        params.iter().rev().fold(0.0, |sum, c| sum * x + c)
+    }
+
+    fn n_params(&self) -> Option<usize> {
+        Some(self.degree + 1)
+    }
+}
+
+/// Crystal Ball function with parameters [norm, mean, sigma, alpha, n], `norm` being its area.
+///
+/// A Gaussian core with a power-law tail of exponent `n` (> 1) starting
+/// `alpha` standard deviations from the mean, on the low side for positive
+/// `alpha` and on the high side for negative `alpha`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrystalBall;
+
+impl Model for CrystalBall {
+    fn evaluate(&self, x: f64, params: &[f64]) -> f64 {
+        let [norm, mean, sigma, alpha, n] = params[..] else {
+            return f64::NAN;
+        };
+        if sigma <= 0.0 || alpha == 0.0 || n <= 1.0 {
+            return f64::NAN;
+        }
+        let abs_alpha = alpha.abs();
+        let t = (x - mean) / sigma * alpha.signum();
+        let tail_norm = n / abs_alpha / (n - 1.0) * (-abs_alpha * abs_alpha / 2.0).exp();
+        let core_norm = (PI / 2.0).sqrt() * (1.0 + erf(abs_alpha / 2f64.sqrt()));
+        let shape = if t > -abs_alpha {
+            (-t * t / 2.0).exp()
+        } else {
+            let a = (n / abs_alpha).powf(n) * (-abs_alpha * abs_alpha / 2.0).exp();
+            let b = n / abs_alpha - abs_alpha;
+            a * (b - t).powf(-n)
+        };
+        norm / (sigma * (tail_norm + core_norm)) * shape
+    }
+
+    fn n_params(&self) -> Option<usize> {
+        Some(5)
+    }
+}
+
+/// Cost function minimized in a fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cost {
+    /// Binned Poisson likelihood, as the likelihood-ratio chi-square of Baker & Cousins.
+    Poisson,
+    /// Chi-square with the bin contents as variances, empty bins are skipped.
+    Neyman,
+    /// Chi-square with the expected contents as variances.
+    Pearson,
+    /// Chi-square with the variances of the histogram, bins without variance are skipped.
+    LeastSquares,
+}
+
+impl FromStr for Cost {
+    type Err = &'static str;
+
+    fn from_str(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "poisson" => Ok(Cost::Poisson),
+            "neyman" => Ok(Cost::Neyman),
+            "pearson" => Ok(Cost::Pearson),
+            "least-squares" => Ok(Cost::LeastSquares),
+            _ => Err("Unknown cost function."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitOptions {
+    pub cost: Cost,
+    /// Integrate the model over the bins instead of evaluating it at the centers.
+    pub integral: bool,
+    pub max_iterations: usize,
+    /// Convergence when the estimated distance to the minimum of the cost is below this.
+    pub tolerance: f64,
+}
+
+impl Default for FitOptions {
+    fn default() -> Self {
+        FitOptions {
+            cost: Cost::Poisson,
+            integral: false,
+            max_iterations: 200,
+            tolerance: 1e-9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitResult {
+    /// Best-fit parameters.
+    pub params: Vec<f64>,
+    /// Standard errors of the parameters, square roots of the covariance diagonal.
+    pub errors: Vec<f64>,
+    pub covariance: Vec<Vec<f64>>,
+    /// Minimum of the cost, chi-square distributed for large contents.
+    pub statistic: f64,
+    /// Number of bins in the fit minus the number of parameters.
+    pub ndf: usize,
+    pub p_value: f64,
+    pub iterations: usize,
+}
+
+// Nodes and weights of the 5-point Gauss–Legendre quadrature on [-1, 1].
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+struct FitBin {
+    lower: f64,
+    upper: f64,
+    value: f64,
+    variance: f64,
+}
+
+// Bins included in the fit with the cost and mode of the fit.
+struct Data {
+    bins: Vec<FitBin>,
+    cost: Cost,
+    integral: bool,
+}
+
+impl Data {
+    fn new<S: Storage>(h1: &H1<'_, S>, options: &FitOptions) -> Self {
+        let edges = h1.axis().bin_edges();
+        let (values, variances) = (h1.values(), h1.variances());
+        let bins = (0..values.len())
+            .map(|i| FitBin {
+                lower: edges[i],
+                upper: edges[i + 1],
+                value: values[i],
+                variance: match options.cost {
+                    Cost::Neyman => values[i],
+                    _ => variances[i],
+                },
+            })
+            .filter(|bin| match options.cost {
+                Cost::Neyman | Cost::LeastSquares => bin.variance > 0.0,
+                Cost::Poisson | Cost::Pearson => true,
+            })
+            .collect();
+        Data {
+            bins,
+            cost: options.cost,
+            integral: options.integral,
+        }
+    }
+
+    fn expected<M: Model + ?Sized>(&self, model: &M, params: &[f64]) -> Vec<f64> {
+        self.bins
+            .iter()
+            .map(|bin| {
+                let (center, half_width) =
+                    ((bin.lower + bin.upper) / 2.0, (bin.upper - bin.lower) / 2.0);
+                if self.integral {
+                    GAUSS_LEGENDRE
+                        .iter()
+                        .map(|(node, weight)| {
+                            weight * model.evaluate(center + node * half_width, params)
+                        })
+                        .sum::<f64>()
+                        * half_width
+                } else {
+                    model.evaluate(center, params) * 2.0 * half_width
+                }
+            })
+            .collect()
+    }
+
+    // Derivatives of the expected contents by central differences, one row per parameter.
+    fn jacobian<M: Model + ?Sized>(&self, model: &M, params: &[f64]) -> Vec<Vec<f64>> {
+        (0..params.len())
+            .map(|j| {
+                let step = 1e-6 * (params[j].abs() + 1e-3);
+                let mut shifted = params.to_vec();
+                shifted[j] = params[j] + step;
+                let up = self.expected(model, &shifted);
+                shifted[j] = params[j] - step;
+                let down = self.expected(model, &shifted);
+                up.iter()
+                    .zip(&down)
+                    .map(|(u, d)| (u - d) / (2.0 * step))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Value of the cost, infinite for invalid expected contents.
+    fn cost(&self, expected: &[f64]) -> f64 {
+        let mut sum = 0.0;
+        for (bin, mu) in self.bins.iter().zip(expected) {
+            let (n, mu) = (bin.value, *mu);
+            let term = match self.cost {
+                Cost::Poisson if mu < 0.0 || (mu == 0.0 && n > 0.0) => f64::INFINITY,
+                Cost::Poisson if n > 0.0 => 2.0 * (mu - n + n * (n / mu).ln()),
+                Cost::Poisson => 2.0 * mu,
+                Cost::Pearson if mu <= 0.0 => f64::INFINITY,
+                Cost::Pearson => (n - mu).powi(2) / mu,
+                Cost::Neyman | Cost::LeastSquares => (n - mu).powi(2) / bin.variance,
+            };
+            sum += term;
+        }
+        if sum.is_nan() {
+            f64::INFINITY
+        } else {
+            sum
+        }
+    }
+
+    // Gradient of the cost and its expected Hessian.
+    fn gradient_hessian(
+        &self,
+        expected: &[f64],
+        jacobian: &[Vec<f64>],
+    ) -> (Vec<f64>, Vec<Vec<f64>>) {
+        let n_params = jacobian.len();
+        let mut gradient = vec![0.0; n_params];
+        let mut hessian = vec![vec![0.0; n_params]; n_params];
+        for (i, (bin, mu)) in self.bins.iter().zip(expected).enumerate() {
+            let (n, mu) = (bin.value, *mu);
+            // Derivative of the cost term by the expected content, and weight of the Hessian
+            let (derivative, weight) = match self.cost {
+                Cost::Poisson if mu > 0.0 => (2.0 * (1.0 - n / mu), 2.0 / mu),
+                Cost::Poisson => (2.0, 0.0),
+                Cost::Pearson => ((mu * mu - n * n) / (mu * mu), 2.0 / mu),
+                Cost::Neyman | Cost::LeastSquares => {
+                    (-2.0 * (n - mu) / bin.variance, 2.0 / bin.variance)
+                }
+            };
+            for j in 0..n_params {
+                gradient[j] += derivative * jacobian[j][i];
+                for k in 0..n_params {
+                    hessian[j][k] += weight * jacobian[j][i] * jacobian[k][i];
+                }
+            }
+        }
+        (gradient, hessian)
+    }
+}
+
+/// Fits a model to the histogram, starting from the `initial` parameters.
+pub fn fit<S: Storage, M: Model + ?Sized>(
+    h1: &H1<'_, S>,
+    model: &M,
+    initial: &[f64],
+    options: &FitOptions,
+) -> Result<FitResult, &'static str> {
+    if initial.is_empty() {
+        return Err("At least one parameter is required.");
+    }
+    if model
+        .n_params()
+        .is_some_and(|n_params| n_params != initial.len())
+    {
+        return Err("Wrong number of parameters for the model.");
+    }
+    let data = Data::new(h1, options);
+    if data.bins.len() <= initial.len() {
+        return Err("More bins than parameters are required.");
+    }
+
+    let mut params = initial.to_vec();
+    let mut expected = data.expected(model, &params);
+    let mut cost = data.cost(&expected);
+    if !cost.is_finite() {
+        return Err("The cost is not finite at the initial parameters.");
+    }
+    let mut damping = 1e-3;
+    let mut iterations = 0;
+    loop {
+        if iterations == options.max_iterations {
+            return Err("The fit did not converge.");
+        }
+        iterations += 1;
+        let jacobian = data.jacobian(model, &params);
+        let (gradient, hessian) = data.gradient_hessian(&expected, &jacobian);
+        let negative_gradient: Vec<f64> = gradient.iter().map(|g| -g).collect();
+        if let Some(newton_step) = solve(&hessian, &negative_gradient) {
+            let distance_to_minimum: f64 = newton_step
+                .iter()
+                .zip(&gradient)
+                .map(|(s, g)| -s * g)
+                .sum::<f64>()
+                / 2.0;
+            if distance_to_minimum < options.tolerance {
+                // Final undamped step, converging quadratically close to the minimum
+                let trial: Vec<f64> = params
+                    .iter()
+                    .zip(&newton_step)
+                    .map(|(p, s)| p + s)
+                    .collect();
+                let trial_expected = data.expected(model, &trial);
+                let trial_cost = data.cost(&trial_expected);
+                if trial_cost <= cost {
+                    (params, expected, cost) = (trial, trial_expected, trial_cost);
+                }
+                break;
+            }
+        }
+
+        // Increase the damping until the cost decreases
+        let mut improved = false;
+        while damping < 1e12 {
+            let mut damped = hessian.clone();
+            for (j, row) in damped.iter_mut().enumerate() {
+                row[j] += damping * (row[j] + f64::EPSILON);
+            }
+            if let Some(step) = solve(&damped, &negative_gradient) {
+                let trial: Vec<f64> = params.iter().zip(&step).map(|(p, s)| p + s).collect();
+                let trial_expected = data.expected(model, &trial);
+                let trial_cost = data.cost(&trial_expected);
+                if trial_cost < cost {
+                    (params, expected, cost) = (trial, trial_expected, trial_cost);
+                    damping = (damping / 10.0).max(1e-9);
+                    improved = true;
+                    break;
+                }
+            }
+            damping *= 10.0;
+        }
+        // No step decreases the cost: the minimum is reached within the precision
+        if !improved {
+            break;
+        }
+    }
+
+    let jacobian = data.jacobian(model, &params);
+    let (_, hessian) = data.gradient_hessian(&expected, &jacobian);
+    let covariance: Vec<Vec<f64>> = invert(&hessian)
+        .ok_or("The covariance matrix is singular.")?
+        .into_iter()
+        .map(|row| row.into_iter().map(|value| 2.0 * value).collect())
+        .collect();
+    let ndf = data.bins.len() - params.len();
+    Ok(FitResult {
+        errors: (0..params.len()).map(|j| covariance[j][j].sqrt()).collect(),
+        params,
+        covariance,
+        statistic: cost,
+        ndf,
+        p_value: chi2_sf(cost, ndf as f64),
+        iterations,
+    })
+}
+
+// Solution of a linear system by Gaussian elimination with partial pivoting.
+fn solve(matrix: &[Vec<f64>], rhs: &[f64]) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    let mut a: Vec<Vec<f64>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, b)| row.iter().copied().chain([*b]).collect())
+        .collect();
+    for column in 0..n {
+        let pivot =
+            (column..n).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+        let pivot_value = a[pivot][column].abs();
+        if pivot_value.is_nan() || pivot_value <= f64::MIN_POSITIVE {
+            return None;
+        }
+        a.swap(column, pivot);
+        let (upper, lower) = a.split_at_mut(column + 1);
+        let pivot_row = &upper[column];
+        for row in lower {
+            let factor = row[column] / pivot_row[column];
+            for (value, pivot_value) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (a[row][n] - sum) / a[row][row];
+    }
+    x.iter().all(|x| x.is_finite()).then_some(x)
+}
+
+fn invert(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let columns = (0..n)
+        .map(|j| {
+            solve(
+                matrix,
+                &(0..n)
+                    .map(|i| if i == j { 1.0 } else { 0.0 })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(
+        (0..n)
+            .map(|i| (0..n).map(|j| columns[j][i]).collect())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::FixedWidthAxis;
+
+    fn h1(min: f64, max: f64, values: &[f64]) -> H1<'static> {
+        let n = values.len();
+        H1::new(
+            Box::new(FixedWidthAxis::new(min, max, (max - min) / n as f64, n)),
+            values.to_vec(),
+        )
+    }
+
+    // Histogram with the model at the bin centers times the bin widths.
+    fn model_h1<M: Model>(model: &M, params: &[f64], min: f64, max: f64, n: usize) -> H1<'static> {
+        let width = (max - min) / n as f64;
+        let values: Vec<f64> = (0..n)
+            .map(|i| model.evaluate(min + (i as f64 + 0.5) * width, params) * width)
+            .collect();
+        h1(min, max, &values)
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    fn options(cost: Cost) -> FitOptions {
+        FitOptions {
+            cost,
+            ..Default::default()
+        }
+    }
+
+    // Reference values below are closed-form solutions evaluated with mpmath.
+
+    #[test]
+    fn test_constant() {
+        let data = h1(0.0, 4.0, &[4.0, 9.0, 16.0, 1.0]);
+        let constant = Polynomial { degree: 0 };
+
+        let result = fit(&data, &constant, &[1.0], &options(Cost::Poisson)).unwrap();
+        assert_close(result.params[0], 7.5, 1e-9);
+        assert_close(result.errors[0], (7.5f64 / 4.0).sqrt(), 1e-6);
+        assert_close(result.statistic, 18.469_055_160_148_188, 1e-9);
+        assert_eq!(result.ndf, 3);
+        assert_close(result.p_value, chi2_sf(result.statistic, 3.0), 1e-12);
+
+        let result = fit(&data, &constant, &[1.0], &options(Cost::Neyman)).unwrap();
+        assert_close(result.params[0], 2.809_756_097_560_975_6, 1e-9);
+        assert_close(result.covariance[0][0], 0.702_439_024_390_243_9, 1e-6);
+        assert_close(result.statistic, 18.760_975_609_756_1, 1e-9);
+
+        let result = fit(&data, &constant, &[1.0], &options(Cost::Pearson)).unwrap();
+        assert_close(result.params[0], 9.407_443_861_113_39, 1e-9);
+        assert_close(result.covariance[0][0], 2.351_860_965_278_347_4, 1e-6);
+        assert_close(result.statistic, 15.259_550_888_907_117, 1e-9);
+    }
+
+    #[test]
+    fn test_least_squares() {
+        let mut data = h1(0.0, 5.0, &[3.0, 5.0, 8.0, 8.0, 12.0]);
+        let line = Polynomial { degree: 1 };
+
+        // Neyman chi-square is least squares with the contents as variances
+        for cost in [Cost::Neyman, Cost::LeastSquares] {
+            let result = fit(&data, &line, &[0.0, 0.0], &options(cost)).unwrap();
+            assert_close(result.params[0], 1.985_568_549_390_395_6, 1e-9);
+            assert_close(result.params[1], 2.060_213_983_578_004_5, 1e-9);
+            assert_close(result.covariance[0][1], -1.140_582_234_386_663_3, 1e-6);
+            assert_close(result.statistic, 0.319_482_458_322_965_9, 1e-9);
+            assert_eq!(result.ndf, 3);
+        }
+
+        data.set_variances(Some(vec![2.0, 2.0, 4.0, 4.0, 8.0]))
+            .unwrap();
+        let result = fit(&data, &line, &[0.0, 0.0], &options(Cost::LeastSquares)).unwrap();
+        assert_close(result.params[0], 2.021_897_810_218_978, 1e-9);
+        assert_close(result.params[1], 2.029_197_080_291_970_8, 1e-9);
+        assert_close(result.covariance[0][0], 1.963_503_649_635_036_5, 1e-6);
+        assert_close(result.covariance[1][1], 0.379_562_043_795_620_4, 1e-6);
+        assert_close(result.statistic, 0.613_138_686_131_386_9, 1e-9);
+
+        // Integrals of a line over the bins are exact
+        let integral = FitOptions {
+            integral: true,
+            ..options(Cost::LeastSquares)
+        };
+        let result = fit(&data, &line, &[0.0, 0.0], &integral).unwrap();
+        assert_close(result.params[1], 2.029_197_080_291_970_8, 1e-9);
+    }
+
+    #[test]
+    fn test_gaussian_integral() {
+        // Integrals of a Gaussian with norm 1000, mean 0.2 and sigma 0.9 over bins of width 0.5
+        let cdf = |x: f64| 500.0 * (1.0 + erf((x - 0.2) / (0.9 * 2f64.sqrt())));
+        let values: Vec<f64> = (0..12)
+            .map(|i| cdf(-3.0 + (i + 1) as f64 * 0.5) - cdf(-3.0 + i as f64 * 0.5))
+            .collect();
+        let data = h1(-3.0, 3.0, &values);
+        let integral = FitOptions {
+            integral: true,
+            ..Default::default()
+        };
+        let result = fit(&data, &Gaussian, &[800.0, 0.0, 1.2], &integral).unwrap();
+        assert_close(result.params[0], 1000.0, 1e-6);
+        assert_close(result.params[1], 0.2, 1e-6);
+        assert_close(result.params[2], 0.9, 1e-6);
+        assert!(result.statistic < 1e-8);
+        assert_eq!(result.ndf, 9);
+
+        // Evaluating at the bin centers widens the Gaussian
+        let result = fit(&data, &Gaussian, &[800.0, 0.0, 1.2], &FitOptions::default()).unwrap();
+        assert!(result.params[2] > 0.905);
+    }
+
+    #[test]
+    fn test_models() {
+        let data = model_h1(&Exponential, &[50.0, -0.7], 0.0, 5.0, 20);
+        let result = fit(&data, &Exponential, &[10.0, -0.1], &FitOptions::default()).unwrap();
+        assert_close(result.params[0], 50.0, 1e-6);
+        assert_close(result.params[1], -0.7, 1e-6);
+
+        let truth = [2000.0, 1.0, 0.5, 1.2, 3.0];
+        let data = model_h1(&CrystalBall, &truth, -3.0, 3.0, 30);
+        let result = fit(
+            &data,
+            &CrystalBall,
+            &[1500.0, 0.8, 0.6, 1.0, 4.0],
+            &FitOptions::default(),
+        )
+        .unwrap();
+        for (param, expected) in result.params.iter().zip(truth) {
+            assert_close(*param, expected, 1e-5);
+        }
+        assert!(result
+            .errors
+            .iter()
+            .all(|error| error.is_finite() && *error > 0.0));
+
+        let closure = |x: f64, params: &[f64]| params[0] * x;
+        let result = fit(
+            &model_h1(&closure, &[3.0], 0.0, 2.0, 4),
+            &closure,
+            &[1.0],
+            &FitOptions::default(),
+        )
+        .unwrap();
+        assert_close(result.params[0], 3.0, 1e-9);
+    }
+
+    #[test]
+    fn test_crystal_ball_norm() {
+        // Midpoint rule over the tail and the core, for both orientations
+        for alpha in [1.5f64, -1.5] {
+            let params = [1.0, 0.0, 1.0, alpha, 3.0];
+            let step = 0.01;
+            let integral: f64 = (0..201_000)
+                .map(|i| {
+                    CrystalBall.evaluate(
+                        alpha.signum() * (-2000.0 + (i as f64 + 0.5) * step),
+                        &params,
+                    ) * step
+                })
+                .sum();
+            assert_close(integral, 1.0, 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let data = h1(0.0, 2.0, &[1.0, 2.0]);
+        assert!(fit(&data, &Gaussian, &[1.0, 0.0], &FitOptions::default()).is_err());
+        assert!(fit(
+            &data,
+            &Polynomial { degree: 2 },
+            &[1.0, 0.0, 0.0],
+            &FitOptions::default()
+        )
+        .is_err());
+        assert!(fit(&data, &Exponential, &[-1.0, 0.0], &FitOptions::default()).is_err());
+        assert_eq!("least-squares".parse(), Ok(Cost::LeastSquares));
+        assert!("chi2".parse::<Cost>().is_err());
+    }
+}
@@ -0,0 +1,472 @@
+//! Versioned binary file format for histograms.
+//!
+//! A file starts with an 8-byte magic header and the format version (major and
+//! minor, both `u16`), followed by a sequence of sections. Each section has
+//! a 4-byte ASCII tag, a `u64` payload length and the payload itself.
+//! All numbers are little-endian. The `DONE` section terminates the file.
+//!
+//! Compatibility rules:
+//! - Each major version has its own reader, which upgrades the contents to the
+//!   current in-memory histogram; 1 is the only one so far. Unknown major
+//!   versions are rejected.
+//! - New minor versions may only add sections. A section whose tag starts with
+//!   an uppercase letter is critical: a reader that does not know it must fail.
+//!   Lowercase tags are ancillary and are skipped if unknown.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::axis::{Axis, FixedWidthAxis, GeneralAxis};
+use crate::h1::{Flow, H1};
+
+pub const MAGIC: [u8; 8] = *b"\x89HISTR\r\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+}
+
+pub const CURRENT_VERSION: Version = Version { major: 1, minor: 0 };
+
+const AXIS_TAG: [u8; 4] = *b"AXIS";
+const CONTENTS_TAG: [u8; 4] = *b"CONT";
+const VARIANCES_TAG: [u8; 4] = *b"VARI";
+const FLOW_TAG: [u8; 4] = *b"flow";
+const METADATA_TAG: [u8; 4] = *b"meta";
+const END_TAG: [u8; 4] = *b"DONE";
+
+const GENERAL_AXIS: u8 = 0;
+const FIXED_WIDTH_AXIS: u8 = 1;
+
+pub fn write_h1(h1: &H1, writer: &mut impl Write) -> std::io::Result<()> {
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&CURRENT_VERSION.major.to_le_bytes())?;
+    writer.write_all(&CURRENT_VERSION.minor.to_le_bytes())?;
+
+    write_section(writer, AXIS_TAG, &encode_axis(h1.axis()))?;
+    write_section(writer, CONTENTS_TAG, &encode_f64s(h1.bin_contents()))?;
+    if h1.has_variances() {
+        write_section(writer, VARIANCES_TAG, &encode_f64s(&h1.variances()))?;
+    }
+    let (underflow, overflow) = (h1.underflow(), h1.overflow());
+    let flow = [
+        underflow.value,
+        underflow.variance,
+        overflow.value,
+        overflow.variance,
+    ];
+    write_section(writer, FLOW_TAG, &encode_f64s(&flow))?;
+    if !h1.metadata().is_empty() {
+        let mut payload = (h1.metadata().len() as u64).to_le_bytes().to_vec();
+        for (key, value) in h1.metadata() {
+            encode_str(&mut payload, key);
+            encode_str(&mut payload, value);
+        }
+        write_section(writer, METADATA_TAG, &payload)?;
+    }
+    write_section(writer, END_TAG, &[])
+}
+
+pub fn read_h1(reader: &mut impl Read) -> Result<H1<'static>, Box<dyn Error>> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err("Not a histr file.".into());
+    }
+    let version = Version {
+        major: read_u16(reader)?,
+        minor: read_u16(reader)?,
+    };
+    match version.major {
+        1 => read_v1(reader),
+        _ => Err("Unsupported format version.".into()),
+    }
+}
+
+/// Reads the sections of a version 1 file, of any minor version.
+///
+/// Upgrade hook: when a new major version is introduced, this function stays
+/// the reader of version 1 files and converts what they contain to the current
+/// `H1`, e.g. with defaults for data that version 1 does not store.
+fn read_v1(reader: &mut impl Read) -> Result<H1<'static>, Box<dyn Error>> {
+    let mut axis: Option<Box<dyn Axis>> = None;
+    let mut contents: Option<Vec<f64>> = None;
+    let mut variances: Option<Vec<f64>> = None;
+    let mut flow = [0.0; 4];
+    let mut metadata = Vec::new();
+
+    loop {
+        let mut tag = [0u8; 4];
+        reader.read_exact(&mut tag)?;
+        let length = read_u64(reader)?;
+        // Read at most the claimed length, without trusting it for the allocation
+        let mut payload = Vec::new();
+        reader.by_ref().take(length).read_to_end(&mut payload)?;
+        if payload.len() as u64 != length {
+            return Err("Truncated section.".into());
+        }
+        let mut payload = payload.as_slice();
+
+        match tag {
+            AXIS_TAG => axis = Some(decode_axis(&mut payload)?),
+            CONTENTS_TAG => contents = Some(decode_f64s(&mut payload)?),
+            VARIANCES_TAG => variances = Some(decode_f64s(&mut payload)?),
+            FLOW_TAG => {
+                let values = decode_f64s(&mut payload)?;
+                if values.len() != 4 {
+                    return Err("Invalid flow section.".into());
+                }
+                flow.copy_from_slice(&values);
+            }
+            METADATA_TAG => {
+                let count = read_u64(&mut payload)?;
+                for _ in 0..count {
+                    let key = decode_str(&mut payload)?;
+                    let value = decode_str(&mut payload)?;
+                    metadata.push((key, value));
+                }
+            }
+            END_TAG => break,
+            _ if tag[0].is_ascii_uppercase() => {
+                return Err("Unknown critical section.".into());
+            }
+            _ => {}
+        }
+    }
+
+    let axis = axis.ok_or("Missing axis section.")?;
+    let contents = contents.ok_or("Missing contents section.")?;
+    if axis.len() != contents.len() {
+        return Err("Axis and contents lengths must match.".into());
+    }
+    let mut h1 = H1::new(axis, contents);
+    h1.set_variances(variances)?;
+    h1.set_underflow(Flow {
+        value: flow[0],
+        variance: flow[1],
+    });
+    h1.set_overflow(Flow {
+        value: flow[2],
+        variance: flow[3],
+    });
+    h1.metadata_mut().extend(metadata);
+    Ok(h1)
+}
+
+pub fn to_bytes(h1: &H1) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_h1(h1, &mut bytes).expect("Writing to a vector cannot fail.");
+    bytes
+}
+
+pub fn from_bytes(mut bytes: &[u8]) -> Result<H1<'static>, Box<dyn Error>> {
+    read_h1(&mut bytes)
+}
+
+impl H1<'_> {
+    /// Write the histogram to a file in the histr format.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_h1(self, &mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl H1<'static> {
+    /// Read a histogram from a file in the histr format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let mut reader = BufReader::new(File::open(path)?);
+        read_h1(&mut reader)
+    }
+}
+
+fn write_section(writer: &mut impl Write, tag: [u8; 4], payload: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&tag)?;
+    writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+    writer.write_all(payload)
+}
+
+fn encode_axis(axis: &dyn Axis) -> Vec<u8> {
+    let mut payload = Vec::new();
+    if let Some(axis) = axis.as_any().downcast_ref::<FixedWidthAxis>() {
+        payload.push(FIXED_WIDTH_AXIS);
+        payload.extend(axis.min_edge().to_le_bytes());
+        payload.extend(axis.max_edge().to_le_bytes());
+        payload.extend(axis.bin_width().to_le_bytes());
+        payload.extend((axis.len() as u64).to_le_bytes());
+    } else {
+        payload.push(GENERAL_AXIS);
+        payload.extend(encode_f64s(&axis.bin_edges()));
+    }
+    payload
+}
+
+fn decode_axis(payload: &mut &[u8]) -> Result<Box<dyn Axis>, Box<dyn Error>> {
+    let mut kind = [0u8; 1];
+    payload.read_exact(&mut kind)?;
+    match kind[0] {
+        GENERAL_AXIS => {
+            let edges = decode_f64s(payload)?;
+            if edges.len() < 2 {
+                return Err("Axis needs at least two edges.".into());
+            }
+            if edges
+                .windows(2)
+                .any(|pair| pair[0].partial_cmp(&pair[1]) != Some(Ordering::Less))
+            {
+                return Err("Axis edges must be increasing.".into());
+            }
+            Ok(Box::new(GeneralAxis::new(edges)))
+        }
+        FIXED_WIDTH_AXIS => {
+            let min_edge = read_f64(payload)?;
+            let max_edge = read_f64(payload)?;
+            let bin_width = read_f64(payload)?;
+            let n_bins = usize::try_from(read_u64(payload)?)?;
+            if n_bins == 0 {
+                return Err("Axis needs at least one bin.".into());
+            }
+            if !(bin_width.is_finite() && bin_width > 0.0) {
+                return Err("Bin width must be finite and positive.".into());
+            }
+            if !(min_edge.is_finite() && max_edge.is_finite() && min_edge < max_edge) {
+                return Err("Axis edges must be finite and increasing.".into());
+            }
+            Ok(Box::new(FixedWidthAxis::new(
+                min_edge, max_edge, bin_width, n_bins,
+            )))
+        }
+        _ => Err("Unknown axis type.".into()),
+    }
+}
+
+fn encode_f64s(values: &[f64]) -> Vec<u8> {
+    let mut payload = (values.len() as u64).to_le_bytes().to_vec();
+    for value in values {
+        payload.extend(value.to_le_bytes());
+    }
+    payload
+}
+
+fn decode_f64s(payload: &mut &[u8]) -> Result<Vec<f64>, Box<dyn Error>> {
+    let count = usize::try_from(read_u64(payload)?)?;
+    if count > payload.len() / 8 {
+        return Err("Truncated section.".into());
+    }
+    (0..count).map(|_| Ok(read_f64(payload)?)).collect()
+}
+
+fn encode_str(payload: &mut Vec<u8>, value: &str) {
+    payload.extend((value.len() as u64).to_le_bytes());
+    payload.extend(value.as_bytes());
+}
+
+fn decode_str(payload: &mut &[u8]) -> Result<String, Box<dyn Error>> {
+    let length = usize::try_from(read_u64(payload)?)?;
+    if length > payload.len() {
+        return Err("Truncated section.".into());
+    }
+    let (bytes, rest) = payload.split_at(length);
+    *payload = rest;
+    Ok(String::from_utf8(bytes.to_vec())?)
+}
+
+fn read_u16(reader: &mut impl Read) -> std::io::Result<u16> {
+    let mut buffer = [0u8; 2];
+    reader.read_exact(&mut buffer)?;
+    Ok(u16::from_le_bytes(buffer))
+}
+
+fn read_u64(reader: &mut impl Read) -> std::io::Result<u64> {
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+fn read_f64(reader: &mut impl Read) -> std::io::Result<f64> {
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(f64::from_le_bytes(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    fn get_h1() -> H1<'static> {
+        let mut h1 = H1::new(
+            Box::new(FixedWidthAxis::create_from_min_and_bins(0.0, 0.5, 4)),
+            vec![0.0; 4],
+        );
+        h1.fill_many(&[-1.0, 0.2, 0.7, 1.9, 5.0]);
+        h1.fill_weighted(1.2, 2.0);
+        h1.metadata_mut().insert("title".into(), "Energy".into());
+        h1
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), Box<dyn Error>> {
+        let h1 = get_h1();
+        let restored = from_bytes(&to_bytes(&h1))?;
+
+        assert!(restored.axis().as_any().is::<FixedWidthAxis>());
+        assert!(restored.axis().equal_bins(h1.axis()));
+        assert_eq!(restored.bin_contents(), h1.bin_contents());
+        assert_eq!(restored.variances(), h1.variances());
+        assert_eq!(restored.underflow(), h1.underflow());
+        assert_eq!(restored.overflow(), h1.overflow());
+        assert_eq!(restored.metadata(), h1.metadata());
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip_general_axis() -> Result<(), Box<dyn Error>> {
+        let h1 = H1::new(Box::new(GeneralAxis::new(vec![0., 1., 5.])), vec![1.0, 2.0]);
+        let restored = from_bytes(&to_bytes(&h1))?;
+
+        assert!(restored.axis().as_any().is::<GeneralAxis>());
+        assert_eq!(restored.axis().bin_edges(), h1.axis().bin_edges());
+        assert!(!restored.has_variances());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<(), Box<dyn Error>> {
+        let path = std::env::temp_dir().join(format!("histr-{}.hst", std::process::id()));
+        let h1 = get_h1();
+        h1.save(&path)?;
+        let restored = H1::load(&path);
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(restored?.bin_contents(), h1.bin_contents());
+        Ok(())
+    }
+
+    // Insert a section right after the header
+    fn with_section(bytes: &[u8], tag: &[u8; 4]) -> Vec<u8> {
+        let mut result = bytes[..12].to_vec();
+        write_section(&mut result, *tag, &[1, 2, 3]).unwrap();
+        result.extend(&bytes[12..]);
+        result
+    }
+
+    #[test]
+    fn test_skips_unknown_ancillary_section() {
+        let bytes = with_section(&to_bytes(&get_h1()), b"xtra");
+        assert!(from_bytes(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unknown_critical_section() {
+        let bytes = with_section(&to_bytes(&get_h1()), b"XTRA");
+        assert!(from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_rejects_other_major_version() {
+        let mut bytes = to_bytes(&get_h1());
+        bytes[8..10].copy_from_slice(&2u16.to_le_bytes());
+        assert!(from_bytes(&bytes).is_err());
+        bytes[8..10].copy_from_slice(&0u16.to_le_bytes());
+        assert!(from_bytes(&bytes).is_err());
+
+        // Newer minor versions are fine
+        let mut bytes = to_bytes(&get_h1());
+        bytes[10..12].copy_from_slice(&7u16.to_le_bytes());
+        assert!(from_bytes(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_truncated_section() {
+        // A section claiming far more bytes than the file has
+        let mut bytes = to_bytes(&get_h1())[..12].to_vec();
+        bytes.extend(AXIS_TAG);
+        bytes.extend(u64::MAX.to_le_bytes());
+        bytes.extend([1, 2, 3]);
+        assert!(from_bytes(&bytes).is_err());
+    }
+
+    // File with the given axis section payload and one bin
+    fn with_axis(axis: &[u8]) -> Vec<u8> {
+        let mut bytes = to_bytes(&get_h1())[..12].to_vec();
+        write_section(&mut bytes, AXIS_TAG, axis).unwrap();
+        write_section(&mut bytes, CONTENTS_TAG, &encode_f64s(&[1.0])).unwrap();
+        write_section(&mut bytes, END_TAG, &[]).unwrap();
+        bytes
+    }
+
+    fn fixed_width_axis(min_edge: f64, max_edge: f64, bin_width: f64, n_bins: u64) -> Vec<u8> {
+        let mut payload = vec![FIXED_WIDTH_AXIS];
+        for value in [min_edge, max_edge, bin_width] {
+            payload.extend(value.to_le_bytes());
+        }
+        payload.extend(n_bins.to_le_bytes());
+        payload
+    }
+
+    #[test]
+    fn test_rejects_invalid_axis() {
+        assert!(from_bytes(&with_axis(&fixed_width_axis(0.0, 1.0, 1.0, 1))).is_ok());
+        assert!(from_bytes(&with_axis(&fixed_width_axis(0.0, 1.0, 1.0, 0))).is_err());
+        assert!(from_bytes(&with_axis(&fixed_width_axis(0.0, 1.0, 0.0, 1))).is_err());
+        assert!(from_bytes(&with_axis(&fixed_width_axis(0.0, 1.0, -1.0, 1))).is_err());
+        assert!(from_bytes(&with_axis(&fixed_width_axis(0.0, 1.0, f64::NAN, 1))).is_err());
+        assert!(from_bytes(&with_axis(&fixed_width_axis(0.0, f64::INFINITY, 1.0, 1))).is_err());
+
+        let mut general = vec![GENERAL_AXIS];
+        general.extend(encode_f64s(&[1.0, 0.0]));
+        assert!(from_bytes(&with_axis(&general)).is_err());
+        let mut general = vec![GENERAL_AXIS];
+        general.extend(encode_f64s(&[0.0, f64::NAN]));
+        assert!(from_bytes(&with_axis(&general)).is_err());
+    }
+
+    #[test]
+    fn test_read_v1_0_fixture() -> Result<(), Box<dyn Error>> {
+        // Generated by fixtures/make_histr_fixtures.py
+        let h1 = from_bytes(include_bytes!("../fixtures/h1_v1.0.hst"))?;
+
+        let axis = h1.axis().as_any().downcast_ref::<FixedWidthAxis>().unwrap();
+        assert_eq!(
+            (
+                axis.min_edge(),
+                axis.max_edge(),
+                axis.bin_width(),
+                axis.len()
+            ),
+            (0.0, 2.0, 0.5, 4)
+        );
+        assert_eq!(h1.bin_contents(), &vec![1.0, 0.0, 2.5, 1.0]);
+        assert_eq!(*h1.variances(), vec![1.0, 0.0, 4.25, 1.0]);
+        assert_eq!(
+            h1.underflow(),
+            Flow {
+                value: 1.0,
+                variance: 1.0
+            }
+        );
+        assert_eq!(
+            h1.overflow(),
+            Flow {
+                value: 2.0,
+                variance: 4.0
+            }
+        );
+        assert_eq!(
+            h1.metadata().get("title").map(String::as_str),
+            Some("Energy")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_invalid_magic() {
+        assert!(from_bytes(b"not a histogram").is_err());
+    }
+}
@@ -1,133 +1,432 @@
 use crate::axis::Axis;
 use crate::bin::Bin;
+use crate::fillable::Fillable;
+use crate::storage::{Counter, Mean, Storage, WeightedStorage};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::ops::{Add, Mul};
 
+/// Sum of weights (and of squared weights) of values that fell outside the axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Flow {
+    pub value: f64,
+    pub variance: f64,
+}
+
+impl Flow {
+    pub(crate) fn add(&mut self, weight: f64) {
+        self.value += weight;
+        self.variance += weight * weight;
+    }
+}
+
+/// One-dimensional histogram, with bin contents of a [`Storage`] type (`f64` by default).
 #[derive(Debug)]
-pub struct H1<'a> {
-    // We will probably want some meta-data here
+pub struct H1<'a, S: Storage = f64> {
     axis: Box<dyn Axis + 'a>,
-    bin_contents: Vec<f64>,
+    bin_contents: Vec<S>,
+    // Sums of squared weights, `None` as long as only unit weights were filled
+    // (and always for storages with their own variances)
+    variances: Option<Vec<f64>>,
+    underflow: Flow,
+    overflow: Flow,
+    metadata: BTreeMap<String, String>,
 }
 
-impl<'a> H1<'a> {
-    pub fn new(axis: Box<dyn Axis>, bin_contents: Vec<f64>) -> Self {
+impl<'a, S: Storage> H1<'a, S> {
+    pub fn new(axis: Box<dyn Axis>, bin_contents: Vec<S>) -> Self {
         if axis.len() != bin_contents.len() {
             panic!("Axis and contents lengths must match.");
         }
-        H1 { axis, bin_contents }
+        H1 {
+            axis,
+            bin_contents,
+            variances: None,
+            underflow: Flow::default(),
+            overflow: Flow::default(),
+            metadata: BTreeMap::new(),
+        }
     }
 
     pub fn axis(&self) -> &dyn Axis {
         self.axis.as_ref()
     }
 
-    pub fn bin_contents(&self) -> &Vec<f64> {
+    pub fn bin_contents(&self) -> &Vec<S> {
         &self.bin_contents
     }
 
+    /// Values of the bins as `f64` (counts, sums of weights or means).
+    pub fn values(&self) -> Cow<'_, Vec<f64>> {
+        S::values(&self.bin_contents)
+    }
+
+    /// Variances of bin contents.
+    ///
+    /// Unless weights were used, these are equal to the contents (Poisson statistics).
+    pub fn variances(&self) -> Cow<'_, Vec<f64>> {
+        match &self.variances {
+            Some(variances) => Cow::Borrowed(variances),
+            None if S::HAS_VARIANCE => Cow::Owned(
+                self.bin_contents
+                    .iter()
+                    .map(|content| content.variance().unwrap_or_default())
+                    .collect(),
+            ),
+            None => self.values(),
+        }
+    }
+
+    /// Whether variances are tracked separately from the contents.
+    pub fn has_variances(&self) -> bool {
+        S::HAS_VARIANCE || self.variances.is_some()
+    }
+
+    pub fn set_variances(&mut self, variances: Option<Vec<f64>>) -> Result<(), &'static str> {
+        if S::HAS_VARIANCE {
+            return Err("Variances are kept by the storage.");
+        }
+        if let Some(variances) = &variances {
+            if variances.len() != self.bin_contents.len() {
+                return Err("Variances and contents lengths must match.");
+            }
+        }
+        self.variances = variances;
+        Ok(())
+    }
+
+    pub fn underflow(&self) -> Flow {
+        self.underflow
+    }
+
+    pub fn set_underflow(&mut self, underflow: Flow) {
+        self.underflow = underflow;
+    }
+
+    pub fn overflow(&self) -> Flow {
+        self.overflow
+    }
+
+    pub fn set_overflow(&mut self, overflow: Flow) {
+        self.overflow = overflow;
+    }
+
+    /// User-defined key-value annotations (title, units, ...).
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.metadata
+    }
+
     pub fn len(&self) -> usize {
         self.axis.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.axis.is_empty()
+    }
+
+    /// Sum of the bin values.
     pub fn total(&self) -> f64 {
-        self.bin_contents.iter().sum()
+        self.bin_contents.iter().map(S::value).sum()
     }
 
-    pub fn get_bin(&self, n: usize) -> Option<Bin> {
-        if let Some(bin_edges) = self.axis().get_bin(n) {
-            Some(Bin {
-                lower: bin_edges.0,
-                upper: bin_edges.1,
-                value: self.bin_contents[n],
-            })
-        } else {
-            None
-        }
+    pub fn get_bin(&self, n: usize) -> Option<Bin<S>> {
+        self.axis().get_bin(n).map(|bin_edges| Bin {
+            lower: bin_edges.0,
+            upper: bin_edges.1,
+            value: self.bin_contents[n].clone(),
+        })
     }
 
-    pub fn fill(&mut self, value: f64) {
-        if let Some(bin) = self.axis.find_bin(value) {
-            self.bin_contents[bin] += 1.0;
+    /// Equality up to a tolerance, as in `|a - b| <= max(rel_tol * max(|a|, |b|), abs_tol)`.
+    ///
+    /// Bin edges, bin values, variances and flow bins are compared, metadata must be equal.
+    pub fn approx_eq(&self, other: &H1<'_, S>, rel_tol: f64, abs_tol: f64) -> bool {
+        let close = |a: f64, b: f64| {
+            a == b || (a - b).abs() <= (rel_tol * a.abs().max(b.abs())).max(abs_tol)
+        };
+        let all_close = |a: &[f64], b: &[f64]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| close(*a, *b))
+        };
+        let flows_close =
+            |a: Flow, b: Flow| close(a.value, b.value) && close(a.variance, b.variance);
+        all_close(&self.axis.bin_edges(), &other.axis.bin_edges())
+            && all_close(&self.values(), &other.values())
+            && all_close(&self.variances(), &other.variances())
+            && flows_close(self.underflow, other.underflow)
+            && flows_close(self.overflow, other.overflow)
+            && self.metadata == other.metadata
+    }
+
+    // Start tracking variances separately (all previous fills had unit weights).
+    fn variances_mut(&mut self) -> &mut Vec<f64> {
+        let values = &self.bin_contents;
+        self.variances
+            .get_or_insert_with(|| S::values(values).into_owned())
+    }
+
+    // Values inside the axis range are not counted.
+    fn fill_flow(&mut self, value: f64, weight: f64) {
+        if value < self.axis.min_edge() {
+            self.underflow.add(weight);
+        } else if value > self.axis.max_edge() {
+            self.overflow.add(weight);
         }
     }
+}
 
-    pub fn fill_weighted(&mut self, value: f64, weight: f64) {
+impl<S: Counter> H1<'_, S> {
+    pub fn fill<T: Fillable>(&mut self, value: T) {
+        let value = value.to_f64();
         if let Some(bin) = self.axis.find_bin(value) {
-            self.bin_contents[bin] += weight;
+            self.bin_contents[bin].fill();
+            if let Some(variances) = &mut self.variances {
+                variances[bin] += 1.0;
+            }
+        } else {
+            self.fill_flow(value, 1.0);
         }
     }
 
-    pub fn fill_many(&mut self, values: &[f64]) {
+    pub fn fill_many<T: Fillable>(&mut self, values: &[T]) {
         self.axis
-            .apply(values)
+            .apply_values(values)
             .iter()
             .enumerate()
-            .for_each(|(bin, value)| {
-                self.bin_contents[bin] += value;
+            .for_each(|(bin, count)| {
+                self.bin_contents[bin].add_count(*count as u64);
+                if let Some(variances) = &mut self.variances {
+                    variances[bin] += count;
+                }
             });
+        values
+            .iter()
+            .for_each(|value| self.fill_flow(value.to_f64(), 1.0));
     }
+}
 
-    pub fn fill_weighted_many(
+impl<S: WeightedStorage> H1<'_, S> {
+    pub fn fill_weighted<T: Fillable>(&mut self, value: T, weight: f64) {
+        let value = value.to_f64();
+        if let Some(bin) = self.axis.find_bin(value) {
+            if !S::HAS_VARIANCE {
+                self.variances_mut()[bin] += weight * weight;
+            }
+            self.bin_contents[bin].fill_weighted(weight);
+        } else {
+            self.fill_flow(value, weight);
+        }
+    }
+
+    pub fn fill_weighted_many<T: Fillable>(
         &mut self,
-        values: &[f64],
+        values: &[T],
         weights: &[f64],
     ) -> Result<(), &'static str> {
-        self.axis
-            .apply_weighted(values, weights)?
-            .iter()
+        let squared_weights: Vec<f64> = weights.iter().map(|w| w * w).collect();
+        let sums = self.axis.apply_weighted_values(values, weights)?;
+        let squared_sums = self.axis.apply_weighted_values(values, &squared_weights)?;
+        if !S::HAS_VARIANCE {
+            let variances = self.variances_mut();
+            squared_sums.iter().enumerate().for_each(|(bin, value)| {
+                variances[bin] += value;
+            });
+        }
+        sums.iter()
+            .zip(&squared_sums)
             .enumerate()
-            .for_each(|(bin, value)| {
-                self.bin_contents[bin] = self.bin_contents[bin] + value;
+            .for_each(|(bin, (sum, sum2))| {
+                self.bin_contents[bin].add_weights(*sum, *sum2);
             });
+        values
+            .iter()
+            .zip(weights)
+            .for_each(|(value, weight)| self.fill_flow(value.to_f64(), *weight));
         Ok(())
     }
 }
 
-impl<'a> Add<&H1<'_>> for &H1<'a> {
-    type Output = Result<H1<'static>, &'static str>;
+impl H1<'_, Mean> {
+    /// Add a sample to the mean of the bin containing the value.
+    pub fn fill_sample<T: Fillable>(&mut self, value: T, sample: f64) {
+        self.fill_weighted_sample(value, sample, 1.0);
+    }
+
+    pub fn fill_weighted_sample<T: Fillable>(&mut self, value: T, sample: f64, weight: f64) {
+        let value = value.to_f64();
+        match self.axis.find_bin(value) {
+            Some(bin) => self.bin_contents[bin].fill_weighted(sample, weight),
+            None => self.fill_flow(value, weight),
+        }
+    }
+}
 
-    fn add(self, other: &H1) -> Result<H1<'static>, &'static str> {
+impl H1<'_> {
+    // Add partial sums of weights (and of squared weights, `None` for unit weights).
+    #[cfg(feature = "parallel")]
+    pub(crate) fn add_sums(
+        &mut self,
+        sums: &[f64],
+        squared_sums: Option<&[f64]>,
+        underflow: Flow,
+        overflow: Flow,
+    ) {
+        match squared_sums {
+            Some(squared_sums) => {
+                let variances = self.variances_mut();
+                variances
+                    .iter_mut()
+                    .zip(squared_sums)
+                    .for_each(|(v, s)| *v += s);
+            }
+            None => {
+                if let Some(variances) = &mut self.variances {
+                    variances.iter_mut().zip(sums).for_each(|(v, s)| *v += s);
+                }
+            }
+        }
+        self.bin_contents
+            .iter_mut()
+            .zip(sums)
+            .for_each(|(c, s)| *c += s);
+        self.underflow.value += underflow.value;
+        self.underflow.variance += underflow.variance;
+        self.overflow.value += overflow.value;
+        self.overflow.variance += overflow.variance;
+    }
+}
+
+impl<'a, S: Storage> Add<&H1<'_, S>> for &H1<'a, S> {
+    type Output = Result<H1<'static, S>, &'static str>;
+
+    fn add(self, other: &H1<'_, S>) -> Result<H1<'static, S>, &'static str> {
         if !self.axis.equal_bins(other.axis()) {
             return Err("Cannot add histograms with different axes.");
         }
+        let variances = if self.variances.is_some() || other.variances.is_some() {
+            Some(
+                self.variances()
+                    .iter()
+                    .zip(other.variances().iter())
+                    .map(|(a, b)| a + b)
+                    .collect(),
+            )
+        } else {
+            None
+        };
         Ok(H1 {
             axis: self.axis.clone_box(), // or not clone?
             bin_contents: self
                 .bin_contents
                 .iter()
                 .zip(other.bin_contents.iter())
-                .map(|(a, b)| a + b)
+                .map(|(a, b)| {
+                    let mut sum = a.clone();
+                    sum.merge(b);
+                    sum
+                })
                 .collect(),
+            variances,
+            underflow: Flow {
+                value: self.underflow.value + other.underflow.value,
+                variance: self.underflow.variance + other.underflow.variance,
+            },
+            overflow: Flow {
+                value: self.overflow.value + other.overflow.value,
+                variance: self.overflow.variance + other.overflow.variance,
+            },
+            metadata: self.metadata.clone(),
         })
     }
 }
 
-impl<'a> Mul<f64> for &H1<'a> {
-    type Output = Result<H1<'static>, &'static str>;
+impl<'a, S: Storage> Mul<f64> for &H1<'a, S> {
+    type Output = Result<H1<'static, S>, &'static str>;
 
-    fn mul(self, other: f64) -> Result<H1<'static>, &'static str> {
+    fn mul(self, other: f64) -> Result<H1<'static, S>, &'static str> {
+        let factor2 = other * other;
+        let variances = if S::HAS_VARIANCE {
+            None
+        } else {
+            Some(self.variances().iter().map(|&v| factor2 * v).collect())
+        };
         Ok(H1 {
             axis: self.axis.clone_box(), // or not clone?
-            bin_contents: self.bin_contents.iter().map(|&a| other * a).collect(),
+            bin_contents: self
+                .bin_contents
+                .iter()
+                .map(|a| {
+                    let mut product = a.clone();
+                    product.scale(other);
+                    product
+                })
+                .collect(),
+            variances,
+            underflow: Flow {
+                value: other * self.underflow.value,
+                variance: factor2 * self.underflow.variance,
+            },
+            overflow: Flow {
+                value: other * self.overflow.value,
+                variance: factor2 * self.overflow.variance,
+            },
+            metadata: self.metadata.clone(),
         })
     }
 }
 
-// TODO: Add support for other numeric types
+impl<S: Storage> Clone for H1<'_, S> {
+    fn clone(&self) -> Self {
+        H1 {
+            axis: self.axis.clone_box(),
+            bin_contents: self.bin_contents.clone(),
+            variances: self.variances.clone(),
+            underflow: self.underflow,
+            overflow: self.overflow,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+/// Histograms are equal if they have the same bins, contents, variances, flows and metadata.
+///
+/// Variances are compared by value, so a histogram filled with unit weights equals
+/// one with the same variances set explicitly. Undefined (NaN) variances, as of
+/// `Mean` bins with less than two samples, are equal to each other.
+impl<S: Storage> PartialEq<H1<'_, S>> for H1<'_, S> {
+    fn eq(&self, other: &H1<'_, S>) -> bool {
+        self.axis.equal_bins(other.axis())
+            && self.bin_contents == other.bin_contents
+            && equal_variances(&self.variances(), &other.variances())
+            && self.underflow == other.underflow
+            && self.overflow == other.overflow
+            && self.metadata == other.metadata
+    }
+}
+
+fn equal_variances(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a == b || (a.is_nan() && b.is_nan()))
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::axis::GeneralAxis;
+    use crate::axis::{FixedWidthAxis, GeneralAxis};
     use crate::bin::Bin;
     use std::error::Error;
 
     fn get_h1() -> H1<'static> {
         // fixture
-        H1 {
-            axis: Box::new(GeneralAxis::new(vec![0., 1., 2., 3.])),
-            bin_contents: vec![1.0, 2.0, 3.0],
-        }
+        H1::new(
+            Box::new(GeneralAxis::new(vec![0., 1., 2., 3.])),
+            vec![1.0, 2.0, 3.0],
+        )
     }
 
     #[test]
@@ -161,6 +460,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_eq() -> Result<(), Box<dyn Error>> {
+        let h1 = get_h1();
+        let mut other = h1.clone();
+        assert_eq!(h1, other);
+
+        other.set_variances(Some(vec![1.0, 2.0, 3.0]))?;
+        assert_eq!(h1, other);
+
+        other.fill(-1.0);
+        assert_ne!(h1, other);
+        Ok(())
+    }
+
+    #[test]
+    fn test_approx_eq() -> Result<(), Box<dyn Error>> {
+        let h1 = get_h1();
+        let scaled = (&h1 * (1.0 + 1e-12))?;
+        assert_ne!(h1, scaled);
+        assert!(h1.approx_eq(&scaled, 1e-9, 0.0));
+        assert!(!h1.approx_eq(&(&h1 * 1.1)?, 1e-9, 0.0));
+        assert!(h1.approx_eq(&(&h1 * 1.1)?, 0.0, 1.0));
+        Ok(())
+    }
+
     #[test]
     fn test_fill() {
         let mut h1 = get_h1();
@@ -181,6 +505,176 @@ mod tests {
         assert_eq!(h1.bin_contents(), &vec![1.0, 3.0, 4.0]);
     }
 
+    #[test]
+    fn test_fill_integers() -> Result<(), Box<dyn Error>> {
+        let mut h1 = get_h1();
+        h1.fill(1u8);
+        h1.fill_many(&[0i32, 2, 3, 5]);
+        h1.fill_weighted_many(&[-1i64, 2], &[2.0, 0.5])?;
+
+        assert_eq!(h1.bin_contents(), &vec![2.0, 3.0, 5.5]);
+        assert_eq!(h1.underflow().value, 2.0);
+        assert_eq!(h1.overflow().value, 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flow() {
+        let mut h1 = get_h1();
+
+        h1.fill_many(&[-5., 1.5, 7.5, 8.5, f64::NAN]);
+        h1.fill_weighted(-1.0, 2.0);
+        assert_eq!(
+            h1.underflow(),
+            Flow {
+                value: 3.0,
+                variance: 5.0
+            }
+        );
+        assert_eq!(
+            h1.overflow(),
+            Flow {
+                value: 2.0,
+                variance: 2.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_flow_partial_last_bin() {
+        // Edges -0.25, 0.25, 0.75, 1.0
+        let axis = || Box::new(FixedWidthAxis::new(-0.25, 1.0, 0.5, 3));
+        let mut h1 = H1::new(axis(), vec![0.0; 3]);
+        h1.fill_many(&[0.9, 1.0, 1.1]);
+        assert_eq!(h1.bin_contents(), &vec![0.0, 0.0, 2.0]);
+        assert_eq!(h1.overflow().value, 1.0);
+
+        let mut single = H1::new(axis(), vec![0.0; 3]);
+        [0.9, 1.0, 1.1]
+            .into_iter()
+            .for_each(|value| single.fill(value));
+        assert_eq!(single, h1);
+    }
+
+    #[test]
+    fn test_variances() -> Result<(), Box<dyn Error>> {
+        let mut h1 = get_h1();
+        assert!(!h1.has_variances());
+        assert_eq!(*h1.variances(), vec![1.0, 2.0, 3.0]);
+
+        h1.fill_weighted(0.5, 3.0);
+        h1.fill(0.5);
+        h1.fill_weighted_many(&[2.5], &[0.5])?;
+        assert!(h1.has_variances());
+        assert_eq!(h1.bin_contents(), &vec![5.0, 2.0, 3.5]);
+        assert_eq!(*h1.variances(), vec![11.0, 2.0, 3.25]);
+
+        let doubled = (&h1 * 2.0)?;
+        assert_eq!(*doubled.variances(), vec![44.0, 8.0, 13.0]);
+
+        let sum = (&h1 + &get_h1())?;
+        assert_eq!(*sum.variances(), vec![12.0, 4.0, 6.25]);
+        Ok(())
+    }
+
+    mod storage {
+        use super::*;
+        use crate::storage::Weight;
+
+        fn get_axis() -> Box<GeneralAxis> {
+            Box::new(GeneralAxis::new(vec![0., 1., 2., 3.]))
+        }
+
+        #[test]
+        fn test_counts() -> Result<(), Box<dyn Error>> {
+            let mut h1: H1<u64> = H1::new(get_axis(), vec![0; 3]);
+            h1.fill(0.5);
+            h1.fill_many(&[1.5, 2.5, 2.7, 4.0]);
+            assert_eq!(h1.bin_contents(), &vec![1, 1, 2]);
+            assert_eq!(h1.overflow().value, 1.0);
+            assert_eq!(h1.get_bin(2).unwrap().value, 2);
+
+            let sum = (&h1 + &h1)?;
+            assert_eq!(sum.bin_contents(), &vec![2, 2, 4]);
+            assert_eq!((&sum * 0.75)?.bin_contents(), &vec![2, 2, 3]);
+            Ok(())
+        }
+
+        #[test]
+        fn test_f32() -> Result<(), Box<dyn Error>> {
+            let mut h1: H1<f32> = H1::new(get_axis(), vec![0.0; 3]);
+            h1.fill_many(&[0.5, 1.5]);
+            h1.fill_weighted_many(&[1.5, 2.5], &[0.5, 2.0])?;
+            assert_eq!(h1.bin_contents(), &vec![1.0f32, 1.5, 2.0]);
+            assert_eq!(*h1.variances(), vec![1.0, 1.25, 4.0]);
+            Ok(())
+        }
+
+        #[test]
+        fn test_weight() -> Result<(), Box<dyn Error>> {
+            let mut h1: H1<Weight> = H1::new(get_axis(), vec![Weight::default(); 3]);
+            let mut expected = H1::new(get_axis(), vec![0.0; 3]);
+            h1.fill(0.5);
+            h1.fill_weighted(0.5, 3.0);
+            h1.fill_weighted_many(&[2.5, 2.5, -1.0], &[0.5, 1.0, 2.0])?;
+            expected.fill(0.5);
+            expected.fill_weighted(0.5, 3.0);
+            expected.fill_weighted_many(&[2.5, 2.5, -1.0], &[0.5, 1.0, 2.0])?;
+
+            assert!(h1.has_variances());
+            assert_eq!(*h1.values(), *expected.values());
+            assert_eq!(*h1.variances(), *expected.variances());
+            assert_eq!(h1.underflow(), expected.underflow());
+            assert!(h1.set_variances(None).is_err());
+
+            let doubled = (&h1 * 2.0)?;
+            assert_eq!(
+                doubled.bin_contents()[0],
+                Weight {
+                    value: 8.0,
+                    variance: 40.0
+                }
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_mean() -> Result<(), Box<dyn Error>> {
+            let mut h1: H1<Mean> = H1::new(get_axis(), vec![Mean::default(); 3]);
+            h1.fill_sample(0.5, 10.0);
+            h1.fill_sample(0.7, 20.0);
+            h1.fill_weighted_sample(2.5, 4.0, 2.0);
+            h1.fill_sample(5.0, 1.0);
+            assert_eq!(*h1.values(), vec![15.0, 0.0, 4.0]);
+            assert_eq!(h1.variances()[0], 25.0);
+            assert_eq!(h1.overflow().value, 1.0);
+
+            let other = h1.clone();
+            let sum = (&h1 + &other)?;
+            assert_eq!(*sum.values(), vec![15.0, 0.0, 4.0]);
+            assert_eq!(sum.bin_contents()[0].sum_of_weights(), 4.0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_eq_mean() {
+        // Empty and single-sample bins have undefined variances
+        let mut h1: H1<Mean> = H1::new(
+            Box::new(GeneralAxis::new(vec![0., 1., 2., 3.])),
+            vec![Mean::default(); 3],
+        );
+        h1.fill_sample(0.5, 1.0);
+        h1.fill_sample(0.5, 2.0);
+        h1.fill_sample(1.5, 3.0);
+        assert!(h1.variances()[1].is_nan() && h1.variances()[2].is_nan());
+        assert_eq!(h1, h1.clone());
+
+        let mut other = h1.clone();
+        other.fill_sample(2.5, 1.0);
+        assert_ne!(h1, other);
+    }
+
     #[test]
     fn test_get_bin() {
         let h1 = get_h1();
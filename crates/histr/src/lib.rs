@@ -1,33 +1,54 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod axis;
 pub mod bin;
 pub mod binnings;
+pub mod concurrent;
+pub mod distance;
+pub mod fit;
+pub mod fillable;
+pub mod format;
 pub mod h1;
+pub mod kde;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod plot;
+#[cfg(feature = "random")]
+pub mod random;
+pub mod render;
+#[cfg(feature = "root")]
+pub mod root;
+pub mod smooth;
+mod special;
+pub mod stats;
+pub mod storage;
+pub mod table;
+pub mod uhi;
 
 use std::error::Error;
 
 use crate::axis::{Axis, GeneralAxis};
 use crate::binnings::{BinningAlgorithm, PrettyBins};
+use crate::fillable::{to_f64_values, Fillable};
 pub use crate::h1::H1;
 
-pub fn h1(data: &[f64]) -> Result<H1<'static>, Box<dyn Error>> {
+pub fn h1<T: Fillable>(data: &[T]) -> Result<H1<'static>, Box<dyn Error>> {
     let binning_algorithm: PrettyBins = PrettyBins { approx_bins: 12 };
-    let axis = binning_algorithm.find_axis(data)?;
-    let values = axis.as_ref().apply(data);
-    Ok(H1::new(axis, values))
+    h1_with_binning(data, &binning_algorithm)
 }
 
-pub fn h1_with_bins(data: &[f64], bins: &[f64]) -> H1<'static> {
-    let axis: Box<GeneralAxis> = Box::from(bins);
-    let values = axis.as_ref().apply(data);
+pub fn h1_with_bins<T: Fillable>(data: &[T], bins: &[f64]) -> H1<'static> {
+    let axis: Box<dyn Axis> = Box::<GeneralAxis>::from(bins);
+    let values = axis.apply_values(data);
     H1::new(axis, values)
 }
 
-pub fn h1_with_binning(
-    data: &[f64],
+pub fn h1_with_binning<T: Fillable>(
+    data: &[T],
     binning: &impl BinningAlgorithm,
 ) -> Result<H1<'static>, Box<dyn Error>> {
-    let axis = binning.find_axis(data)? as Box<dyn Axis>;
-    let values = axis.as_ref().apply(data);
+    let axis = binning.find_axis(&to_f64_values(data))? as Box<dyn Axis>;
+    let values = axis.apply_values(data);
     Ok(H1::new(axis, values))
 }
 
@@ -73,6 +94,16 @@ mod tests {
             assert_eq!(lower, 0.0);
             assert_eq!(upper, 0.1);
         }
+
+        #[test]
+        fn from_integers() {
+            let data: Vec<u16> = vec![3, 4, 4, 10];
+            let h = h1(&data).unwrap();
+            let floats: Vec<f64> = data.iter().map(|value| f64::from(*value)).collect();
+
+            assert_eq!(h, h1(&floats).unwrap());
+            assert_eq!(h1_with_bins(&[1i32, 5, 6], &[0.0, 5.0, 10.0]).bin_contents(), &vec![1.0, 2.0]);
+        }
     }
 
     mod h1_macro {
@@ -82,6 +113,8 @@ mod tests {
         fn works() {
             let _h = h1!(&[0.0, 1.0]).unwrap();
             let _h = h1!(&[0.0, 1.0], bin_width: 0.1).unwrap();
+            let _h = h1!(&[0i32, 1], bin_width: 0.1).unwrap();
+            let _h = h1!(&[0.0f32, 1.0], bin_edges: &[0.0, 0.5, 1.0]);
         }
     }
 }
@@ -0,0 +1,202 @@
+//! Multithreaded filling with rayon.
+//!
+//! The input is split into chunks, each chunk is histogrammed into its own
+//! buffer and the buffers are summed. Unweighted results are identical to the
+//! serial `fill_many`; weighted sums may differ by rounding.
+
+use rayon::prelude::*;
+
+use crate::axis::Axis;
+use crate::fillable::Fillable;
+use crate::h1::{Flow, H1};
+
+const CHUNK_SIZE: usize = 1 << 14;
+
+// Sums for a part of the input
+struct Partial {
+    sums: Vec<f64>,
+    squared_sums: Vec<f64>,
+    underflow: Flow,
+    overflow: Flow,
+}
+
+impl Partial {
+    fn new(n_bins: usize, weighted: bool) -> Self {
+        Partial {
+            sums: vec![0.0; n_bins],
+            squared_sums: if weighted {
+                vec![0.0; n_bins]
+            } else {
+                Vec::new()
+            },
+            underflow: Flow::default(),
+            overflow: Flow::default(),
+        }
+    }
+
+    fn fill(&mut self, axis: &dyn Axis, value: f64, weight: f64, weighted: bool) {
+        match axis.find_bin(value) {
+            Some(bin) => {
+                self.sums[bin] += weight;
+                if weighted {
+                    self.squared_sums[bin] += weight * weight;
+                }
+            }
+            None if value < axis.min_edge() => self.underflow.add(weight),
+            None if value > axis.max_edge() => self.overflow.add(weight),
+            None => {}
+        }
+    }
+
+    fn merge(mut self, other: Partial) -> Partial {
+        let add = |a: &mut Vec<f64>, b: &[f64]| a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+        add(&mut self.sums, &other.sums);
+        add(&mut self.squared_sums, &other.squared_sums);
+        self.underflow.value += other.underflow.value;
+        self.underflow.variance += other.underflow.variance;
+        self.overflow.value += other.overflow.value;
+        self.overflow.variance += other.overflow.variance;
+        self
+    }
+}
+
+impl H1<'_> {
+    /// Parallel version of `fill_many`.
+    pub fn fill_many_par<T: Fillable>(&mut self, values: &[T]) {
+        let axis = self.axis();
+        let n_bins = axis.len();
+        let partial = values
+            .par_chunks(CHUNK_SIZE)
+            .map(|chunk| {
+                let mut partial = Partial::new(n_bins, false);
+                chunk
+                    .iter()
+                    .for_each(|value| partial.fill(axis, value.to_f64(), 1.0, false));
+                partial
+            })
+            .reduce(|| Partial::new(n_bins, false), Partial::merge);
+        self.add_sums(&partial.sums, None, partial.underflow, partial.overflow);
+    }
+
+    /// Parallel version of `fill_weighted_many`.
+    pub fn fill_weighted_many_par<T: Fillable>(
+        &mut self,
+        values: &[T],
+        weights: &[f64],
+    ) -> Result<(), &'static str> {
+        if values.len() != weights.len() {
+            return Err("Data and weights must have the same length.");
+        }
+        let axis = self.axis();
+        let n_bins = axis.len();
+        let partial = values
+            .par_chunks(CHUNK_SIZE)
+            .zip(weights.par_chunks(CHUNK_SIZE))
+            .map(|(values, weights)| {
+                let mut partial = Partial::new(n_bins, true);
+                values
+                    .iter()
+                    .zip(weights)
+                    .for_each(|(value, weight)| partial.fill(axis, value.to_f64(), *weight, true));
+                partial
+            })
+            .reduce(|| Partial::new(n_bins, true), Partial::merge);
+        self.add_sums(
+            &partial.sums,
+            Some(&partial.squared_sums),
+            partial.underflow,
+            partial.overflow,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::GeneralAxis;
+    use crate::binnings::{BinningAlgorithm, FixedWidthBins};
+    use std::error::Error;
+
+    fn get_h1() -> H1<'static> {
+        H1::new(
+            Box::new(GeneralAxis::new(vec![0., 1., 2., 4., 8.])),
+            vec![0.0; 4],
+        )
+    }
+
+    // Deterministic values spread over [-1, 9)
+    fn get_values(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| ((i * 7919) % 10_000) as f64 / 1000.0 - 1.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_fill_many_par() {
+        let values = get_values(100_000);
+        let mut serial = get_h1();
+        serial.fill_many(&values);
+        let mut parallel = get_h1();
+        parallel.fill_many_par(&values);
+
+        assert_eq!(parallel, serial);
+        assert!(!parallel.has_variances());
+        assert_eq!(parallel.underflow().value, 10_000.0);
+    }
+
+    #[test]
+    fn test_fill_many_par_integers() {
+        let values: Vec<i32> = (0..100_000).map(|i| (i * 7919) % 11 - 1).collect();
+        let mut serial = get_h1();
+        serial.fill_many(&values);
+        let mut parallel = get_h1();
+        parallel.fill_many_par(&values);
+
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn test_fill_many_par_partial_last_bin() -> Result<(), Box<dyn Error>> {
+        // Edges -0.25, 0.25, 0.75, 1.0, values up to 1.25 are beyond the last edge
+        let axis = FixedWidthBins { bin_width: 0.5 }.find_axis_in_range(&[], (-0.25, 1.0))?;
+        let values: Vec<f64> = (0..100_000)
+            .map(|i| (i % 200) as f64 * 0.01 - 0.5)
+            .collect();
+        let mut serial = H1::new(axis.clone(), vec![0.0; 3]);
+        serial.fill_many(&values);
+        let mut parallel = H1::new(axis, vec![0.0; 3]);
+        parallel.fill_many_par(&values);
+
+        assert_eq!(parallel, serial);
+        assert_eq!(parallel.overflow().value, 24_500.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_weighted_many_par() -> Result<(), Box<dyn Error>> {
+        let values = get_values(100_000);
+        let weights: Vec<f64> = (0..values.len()).map(|i| (i % 3) as f64 * 0.5).collect();
+        let mut serial = get_h1();
+        serial.fill_weighted_many(&values, &weights)?;
+        let mut parallel = get_h1();
+        parallel.fill_weighted_many_par(&values, &weights)?;
+
+        assert!(parallel.approx_eq(&serial, 1e-12, 0.0));
+        assert!(parallel
+            .fill_weighted_many_par(&values, &weights[1..])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_after_weighted() -> Result<(), Box<dyn Error>> {
+        let mut h1 = get_h1();
+        h1.fill_weighted(0.5, 2.0);
+        h1.fill_many_par(&[0.5, 3.0]);
+
+        assert_eq!(h1.bin_contents(), &vec![3.0, 0.0, 1.0, 0.0]);
+        assert_eq!(*h1.variances(), vec![5.0, 0.0, 1.0, 0.0]);
+        Ok(())
+    }
+}
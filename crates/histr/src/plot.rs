@@ -0,0 +1,895 @@
+//! SVG plots of histograms.
+//!
+//! One-dimensional histograms are drawn as steps, bars or error bars, several
+//! of them can be overlaid in one plot (with a legend for labelled layers).
+//! Two-dimensional data are drawn as heatmaps with a colorbar, using the
+//! viridis colormap. Axis ticks are placed at "pretty" values (multiples of 1,
+//! 2, 2.5 or 5 times a power of ten), or at powers of ten on log axes.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::axis::Axis;
+use crate::binnings::find_pretty_width;
+use crate::h1::H1;
+use crate::render::{edge_precision, format_value};
+use crate::storage::Storage;
+
+const PALETTE: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+];
+
+// Control points of the viridis colormap, interpolated linearly.
+const VIRIDIS: [(u8, u8, u8); 5] = [
+    (0x44, 0x01, 0x54),
+    (0x3b, 0x52, 0x8b),
+    (0x21, 0x91, 0x8c),
+    (0x5e, 0xc9, 0x62),
+    (0xfd, 0xe7, 0x25),
+];
+
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 20.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+const TITLE_HEIGHT: f64 = 20.0;
+const COLORBAR_WIDTH: f64 = 15.0;
+// Space taken by the colorbar with its gap and tick labels
+const COLORBAR_SPACE: f64 = 80.0;
+const TICK_LENGTH: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotStyle {
+    /// Outline of the bin contents.
+    Step,
+    /// Filled bars.
+    Bar,
+    /// Markers at the bin centers with error bars (square roots of the variances).
+    Errorbar,
+}
+
+impl FromStr for PlotStyle {
+    type Err = &'static str;
+
+    fn from_str(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "step" => Ok(PlotStyle::Step),
+            "bar" => Ok(PlotStyle::Bar),
+            "errorbar" => Ok(PlotStyle::Errorbar),
+            _ => Err("Unknown plot style."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotOptions {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    pub title: Option<String>,
+    pub x_label: Option<String>,
+    pub y_label: Option<String>,
+    pub log_x: bool,
+    pub log_y: bool,
+    /// Logarithmic color scale of heatmaps.
+    pub log_z: bool,
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        PlotOptions {
+            width: 640,
+            height: 480,
+            title: None,
+            x_label: None,
+            y_label: None,
+            log_x: false,
+            log_y: false,
+            log_z: false,
+        }
+    }
+}
+
+/// Histogram drawn in an overlay plot.
+#[derive(Debug, Clone, Copy)]
+pub struct Layer<'p, S: Storage = f64> {
+    pub h1: &'p H1<'p, S>,
+    pub style: PlotStyle,
+    /// Legend entry, layers without label are not listed.
+    pub label: Option<&'p str>,
+}
+
+impl<'p, S: Storage> Layer<'p, S> {
+    pub fn new(h1: &'p H1<'p, S>, style: PlotStyle) -> Self {
+        Layer {
+            h1,
+            style,
+            label: None,
+        }
+    }
+}
+
+// Maps data values to pixels.
+struct Scale {
+    min: f64,
+    max: f64,
+    log: bool,
+    start: f64,
+    end: f64,
+}
+
+impl Scale {
+    fn new((min, max): (f64, f64), log: bool, start: f64, end: f64) -> Self {
+        Scale {
+            min,
+            max,
+            log,
+            start,
+            end,
+        }
+    }
+
+    fn map(&self, value: f64) -> f64 {
+        let fraction = if self.log {
+            if value <= 0.0 {
+                return self.start;
+            }
+            (value / self.min).ln() / (self.max / self.min).ln()
+        } else {
+            (value - self.min) / (self.max - self.min)
+        };
+        self.start + fraction * (self.end - self.start)
+    }
+
+    // Tick values with their labels (SVG text content).
+    fn ticks(&self) -> Vec<(f64, String)> {
+        if self.log {
+            let decades = log_ticks(self.min, self.max);
+            if decades.len() >= 2 {
+                return decades
+                    .into_iter()
+                    .map(|exponent| {
+                        let label =
+                            format!("10<tspan dy=\"-5\" font-size=\"9\">{exponent}</tspan>");
+                        (10f64.powi(exponent), label)
+                    })
+                    .collect();
+            }
+            // Less than two decades: 1, 2 and 5 times powers of ten
+            let mut ticks = Vec::new();
+            for exponent in (self.min.log10().floor() as i32)..=(self.max.log10().ceil() as i32) {
+                for factor in [1.0, 2.0, 5.0] {
+                    let value = factor * 10f64.powi(exponent);
+                    if is_within(value, self.min, self.max) {
+                        ticks.push((value, format_value(value)));
+                    }
+                }
+            }
+            return ticks;
+        }
+        let values = linear_ticks(self.min, self.max);
+        let precision = edge_precision(&values);
+        values
+            .into_iter()
+            .map(|value| {
+                let label = format!("{:.p$}", value, p = precision);
+                let label = if label
+                    .trim_start_matches('-')
+                    .trim_matches(['0', '.'])
+                    .is_empty()
+                {
+                    label.trim_start_matches('-').to_string()
+                } else {
+                    label
+                };
+                (value, label)
+            })
+            .collect()
+    }
+}
+
+fn is_within(value: f64, min: f64, max: f64) -> bool {
+    let tolerance = 1e-9 * (max - min).abs();
+    value >= min - tolerance && value <= max + tolerance
+}
+
+fn tick_step(min: f64, max: f64) -> f64 {
+    find_pretty_width((max - min) / 5.0)
+}
+
+fn linear_ticks(min: f64, max: f64) -> Vec<f64> {
+    let step = tick_step(min, max);
+    let first = (min / step - 1e-9).ceil() as i64;
+    let last = (max / step + 1e-9).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+// Exponents of the powers of ten within the range.
+fn log_ticks(min: f64, max: f64) -> Vec<i32> {
+    let first = (min.log10() - 1e-9).ceil() as i32;
+    let last = (max.log10() + 1e-9).floor() as i32;
+    (first..=last).collect()
+}
+
+// Range of the data extended to tick values (to zero for linear scales).
+fn value_range(values: impl Iterator<Item = f64>, log: bool) -> (f64, f64) {
+    if log {
+        let (min, max) = values
+            .filter(|value| *value > 0.0 && value.is_finite())
+            .fold((f64::INFINITY, 0.0f64), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        if max == 0.0 {
+            return (1.0, 10.0);
+        }
+        // Values at the lowest decade should not sit on the frame
+        let min = 10f64.powi((min / 2.0).log10().floor() as i32);
+        let max = 10f64.powi(max.log10().ceil() as i32);
+        return if max > min {
+            (min, max)
+        } else {
+            (min, min * 10.0)
+        };
+    }
+    let (min, max) = values
+        .filter(|value| value.is_finite())
+        .fold((0.0f64, 0.0f64), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if max <= min {
+        return (min, min + 1.0);
+    }
+    let step = tick_step(min, max);
+    ((min / step).floor() * step, (max / step).ceil() * step)
+}
+
+// Range of bin edges, from the smallest positive edge on log scales.
+fn edge_range(edges: impl Iterator<Item = f64>, log: bool) -> (f64, f64) {
+    let (min, max) = edges
+        .filter(|edge| edge.is_finite() && (!log || *edge > 0.0))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), edge| {
+            (min.min(edge), max.max(edge))
+        });
+    if min > max {
+        if log {
+            (1.0, 10.0)
+        } else {
+            (0.0, 1.0)
+        }
+    } else if max == min {
+        if log {
+            (min, min * 10.0)
+        } else {
+            (min, min + 1.0)
+        }
+    } else {
+        (min, max)
+    }
+}
+
+/// Color of the viridis colormap for a fraction between 0 and 1.
+fn colormap(fraction: f64) -> String {
+    let position = fraction.clamp(0.0, 1.0) * (VIRIDIS.len() - 1) as f64;
+    let index = (position.floor() as usize).min(VIRIDIS.len() - 2);
+    let t = position - index as f64;
+    let (r0, g0, b0) = VIRIDIS[index];
+    let (r1, g1, b1) = VIRIDIS[index + 1];
+    let mix = |a: u8, b: u8| (f64::from(a) + t * (f64::from(b) - f64::from(a))).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", mix(r0, r1), mix(g0, g1), mix(b0, b1))
+}
+
+// Pixel coordinate with at most two decimals.
+fn px(value: f64) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Plot area in pixels.
+struct Frame {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+}
+
+impl Frame {
+    fn new(options: &PlotOptions, extra_right: f64) -> Self {
+        let top = MARGIN_TOP
+            + if options.title.is_some() {
+                TITLE_HEIGHT
+            } else {
+                0.0
+            };
+        let bottom = f64::from(options.height) - MARGIN_BOTTOM;
+        let right = f64::from(options.width) - MARGIN_RIGHT - extra_right;
+        Frame {
+            left: MARGIN_LEFT,
+            top,
+            right: right.max(MARGIN_LEFT + 1.0),
+            bottom: bottom.max(top + 1.0),
+        }
+    }
+
+    fn x_scale(&self, range: (f64, f64), log: bool) -> Scale {
+        Scale::new(range, log, self.left, self.right)
+    }
+
+    fn y_scale(&self, range: (f64, f64), log: bool) -> Scale {
+        Scale::new(range, log, self.bottom, self.top)
+    }
+}
+
+// Start of the document, `defs` are added to the definitions.
+fn open_svg(options: &PlotOptions, frame: &Frame, defs: &str) -> String {
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" \
+         font-family=\"sans-serif\" font-size=\"12\">",
+        options.width, options.height
+    );
+    let _ = writeln!(
+        svg,
+        "  <rect width=\"100%\" height=\"100%\" fill=\"white\"/>"
+    );
+    let _ = writeln!(
+        svg,
+        "  <defs>\n    <clipPath id=\"histr-plot-area\">\n      \
+         <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\n    </clipPath>\n{defs}  </defs>",
+        px(frame.left),
+        px(frame.top),
+        px(frame.right - frame.left),
+        px(frame.bottom - frame.top)
+    );
+    svg
+}
+
+// Frame, ticks, axis labels and title.
+fn write_axes(
+    svg: &mut String,
+    frame: &Frame,
+    x_scale: &Scale,
+    y_scale: &Scale,
+    options: &PlotOptions,
+) {
+    let _ = writeln!(
+        svg,
+        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>",
+        px(frame.left),
+        px(frame.top),
+        px(frame.right - frame.left),
+        px(frame.bottom - frame.top)
+    );
+    let _ = writeln!(svg, "  <g class=\"x-ticks\" text-anchor=\"middle\">");
+    for (value, label) in x_scale.ticks() {
+        let x = px(x_scale.map(value));
+        let _ = writeln!(
+            svg,
+            "    <line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"black\"/>",
+            px(frame.bottom),
+            px(frame.bottom + TICK_LENGTH)
+        );
+        let _ = writeln!(
+            svg,
+            "    <text x=\"{x}\" y=\"{}\">{label}</text>",
+            px(frame.bottom + 19.0)
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+    let _ = writeln!(svg, "  <g class=\"y-ticks\" text-anchor=\"end\">");
+    for (value, label) in y_scale.ticks() {
+        let y = y_scale.map(value);
+        let _ = writeln!(
+            svg,
+            "    <line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\"/>",
+            px(frame.left - TICK_LENGTH),
+            px(frame.left),
+            y = px(y)
+        );
+        let _ = writeln!(
+            svg,
+            "    <text x=\"{}\" y=\"{}\">{label}</text>",
+            px(frame.left - 8.0),
+            px(y + 4.0)
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+
+    let center_x = px((frame.left + frame.right) / 2.0);
+    if let Some(title) = &options.title {
+        let _ = writeln!(
+            svg,
+            "  <text x=\"{center_x}\" y=\"{}\" text-anchor=\"middle\" font-size=\"14\">{}</text>",
+            px(MARGIN_TOP + 6.0),
+            escape(title)
+        );
+    }
+    if let Some(x_label) = &options.x_label {
+        let _ = writeln!(
+            svg,
+            "  <text x=\"{center_x}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            px(frame.bottom + 40.0),
+            escape(x_label)
+        );
+    }
+    if let Some(y_label) = &options.y_label {
+        let center_y = px((frame.top + frame.bottom) / 2.0);
+        let _ = writeln!(
+            svg,
+            "  <text x=\"16\" y=\"{center_y}\" text-anchor=\"middle\" transform=\"rotate(-90 16 {center_y})\">{}</text>",
+            escape(y_label)
+        );
+    }
+}
+
+fn write_layer<S: Storage>(
+    svg: &mut String,
+    layer: &Layer<'_, S>,
+    color: &str,
+    x_scale: &Scale,
+    y_scale: &Scale,
+) {
+    let values = layer.h1.values();
+    let edges = layer.h1.axis().bin_edges();
+    let xs: Vec<f64> = edges.iter().map(|edge| x_scale.map(*edge)).collect();
+    let baseline = y_scale.map(if y_scale.log { y_scale.min } else { 0.0 });
+    let y = |value: f64| {
+        if value.is_nan() {
+            baseline
+        } else {
+            y_scale.map(value)
+        }
+    };
+
+    match layer.style {
+        PlotStyle::Step => {
+            let mut path = format!("M{},{}", px(xs[0]), px(baseline));
+            for (i, value) in values.iter().enumerate() {
+                let _ = write!(path, " V{} H{}", px(y(*value)), px(xs[i + 1]));
+            }
+            let _ = write!(path, " V{}", px(baseline));
+            let _ = writeln!(
+                svg,
+                "    <path d=\"{path}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\"/>"
+            );
+        }
+        PlotStyle::Bar => {
+            let _ = writeln!(
+                svg,
+                "    <g fill=\"{color}\" fill-opacity=\"0.6\" stroke=\"{color}\">"
+            );
+            for (i, value) in values.iter().enumerate() {
+                let top = y(*value);
+                let _ = writeln!(
+                    svg,
+                    "      <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                    px(xs[i]),
+                    px(top.min(baseline)),
+                    px(xs[i + 1] - xs[i]),
+                    px((baseline - top).abs())
+                );
+            }
+            let _ = writeln!(svg, "    </g>");
+        }
+        PlotStyle::Errorbar => {
+            let variances = layer.h1.variances();
+            let _ = writeln!(svg, "    <g stroke=\"{color}\" fill=\"{color}\">");
+            for (i, value) in values.iter().enumerate() {
+                if !value.is_finite() || (y_scale.log && *value <= 0.0) {
+                    continue;
+                }
+                let error = variances[i].sqrt();
+                let center = px((xs[i] + xs[i + 1]) / 2.0);
+                let y_value = px(y(*value));
+                if error > 0.0 {
+                    let _ = writeln!(
+                        svg,
+                        "      <line x1=\"{center}\" y1=\"{}\" x2=\"{center}\" y2=\"{}\"/>",
+                        px(y_scale.map(value - error)),
+                        px(y_scale.map(value + error))
+                    );
+                }
+                let _ = writeln!(
+                    svg,
+                    "      <line x1=\"{}\" y1=\"{y_value}\" x2=\"{}\" y2=\"{y_value}\"/>",
+                    px(xs[i]),
+                    px(xs[i + 1])
+                );
+                let _ = writeln!(
+                    svg,
+                    "      <circle cx=\"{center}\" cy=\"{y_value}\" r=\"2.5\"/>"
+                );
+            }
+            let _ = writeln!(svg, "    </g>");
+        }
+    }
+}
+
+fn write_legend<S: Storage>(svg: &mut String, layers: &[Layer<'_, S>], frame: &Frame) {
+    let entries: Vec<(usize, &Layer<'_, S>, &str)> = layers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, layer)| layer.label.map(|label| (i, layer, label)))
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+    // Approximate width of the text
+    let text_width = entries
+        .iter()
+        .map(|(_, _, label)| label.chars().count())
+        .max()
+        .unwrap_or(0) as f64
+        * 7.0;
+    let width = text_width + 40.0;
+    let left = frame.right - 10.0 - width;
+    let top = frame.top + 10.0;
+    let _ = writeln!(svg, "  <g class=\"legend\">");
+    let _ = writeln!(
+        svg,
+        "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"#cccccc\"/>",
+        px(left),
+        px(top),
+        px(width),
+        px(entries.len() as f64 * 18.0 + 6.0)
+    );
+    for (row, (i, layer, label)) in entries.iter().enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        let y = top + 15.0 + row as f64 * 18.0;
+        let swatch = match layer.style {
+            PlotStyle::Step => format!(
+                "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"{color}\" stroke-width=\"1.5\"/>",
+                px(left + 8.0),
+                px(left + 24.0),
+                y = px(y - 4.0)
+            ),
+            PlotStyle::Bar => format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"16\" height=\"10\" fill=\"{color}\" fill-opacity=\"0.6\" stroke=\"{color}\"/>",
+                px(left + 8.0),
+                px(y - 9.0)
+            ),
+            PlotStyle::Errorbar => format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"2.5\" fill=\"{color}\"/>",
+                px(left + 16.0),
+                px(y - 4.0)
+            ),
+        };
+        let _ = writeln!(svg, "    {swatch}");
+        let _ = writeln!(
+            svg,
+            "    <text x=\"{}\" y=\"{}\">{}</text>",
+            px(left + 32.0),
+            px(y),
+            escape(label)
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+}
+
+/// Plot of a histogram as SVG.
+pub fn plot_svg<S: Storage>(h1: &H1<'_, S>, style: PlotStyle, options: &PlotOptions) -> String {
+    overlay_svg(&[Layer::new(h1, style)], options)
+}
+
+/// Plot of several histograms on common axes as SVG.
+///
+/// Layers are drawn in order with colors of a fixed palette.
+pub fn overlay_svg<S: Storage>(layers: &[Layer<'_, S>], options: &PlotOptions) -> String {
+    let frame = Frame::new(options, 0.0);
+    let x_range = edge_range(
+        layers
+            .iter()
+            .flat_map(|layer| layer.h1.axis().bin_edges().into_owned()),
+        options.log_x,
+    );
+    let y_range = value_range(
+        layers.iter().flat_map(|layer| {
+            let values = layer.h1.values().into_owned();
+            let errors: Vec<f64> = match layer.style {
+                PlotStyle::Errorbar => layer
+                    .h1
+                    .variances()
+                    .iter()
+                    .map(|variance| variance.sqrt())
+                    .collect(),
+                _ => vec![0.0; values.len()],
+            };
+            values
+                .into_iter()
+                .zip(errors)
+                .map(|(value, error)| value + error)
+        }),
+        options.log_y,
+    );
+    let x_scale = frame.x_scale(x_range, options.log_x);
+    let y_scale = frame.y_scale(y_range, options.log_y);
+
+    let mut svg = open_svg(options, &frame, "");
+    let _ = writeln!(svg, "  <g clip-path=\"url(#histr-plot-area)\">");
+    for (i, layer) in layers.iter().enumerate() {
+        if !layer.h1.is_empty() {
+            write_layer(
+                &mut svg,
+                layer,
+                PALETTE[i % PALETTE.len()],
+                &x_scale,
+                &y_scale,
+            );
+        }
+    }
+    let _ = writeln!(svg, "  </g>");
+    write_axes(&mut svg, &frame, &x_scale, &y_scale, options);
+    write_legend(&mut svg, layers, &frame);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Heatmap of two-dimensional bin contents with a colorbar as SVG.
+///
+/// The value of bin `i` of the x axis and `j` of the y axis is
+/// `values[i * y_axis.len() + j]`. Bins with non-finite values (or
+/// non-positive values on a log color scale) are left blank.
+pub fn heatmap_svg(
+    x_axis: &dyn Axis,
+    y_axis: &dyn Axis,
+    values: &[f64],
+    options: &PlotOptions,
+) -> Result<String, &'static str> {
+    if values.len() != x_axis.len() * y_axis.len() {
+        return Err("Number of values must match the number of bins.");
+    }
+    let frame = Frame::new(options, COLORBAR_SPACE);
+    let x_edges = x_axis.bin_edges();
+    let y_edges = y_axis.bin_edges();
+    let x_scale = frame.x_scale(
+        edge_range(x_edges.iter().copied(), options.log_x),
+        options.log_x,
+    );
+    let y_scale = frame.y_scale(
+        edge_range(y_edges.iter().copied(), options.log_y),
+        options.log_y,
+    );
+
+    let visible = |value: &f64| value.is_finite() && (!options.log_z || *value > 0.0);
+    let (z_min, z_max) = values
+        .iter()
+        .filter(|value| visible(value))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        });
+    let z_range = if z_min > z_max {
+        if options.log_z {
+            (1.0, 10.0)
+        } else {
+            (0.0, 1.0)
+        }
+    } else if options.log_z {
+        (z_min, if z_max > z_min { z_max } else { z_min * 10.0 })
+    } else {
+        let z_min = z_min.min(0.0);
+        (z_min, if z_max > z_min { z_max } else { z_min + 1.0 })
+    };
+    let z_scale = Scale::new(z_range, options.log_z, 0.0, 1.0);
+
+    let mut gradient = String::from(
+        "    <linearGradient id=\"histr-colormap\" x1=\"0\" y1=\"1\" x2=\"0\" y2=\"0\">\n",
+    );
+    for i in 0..VIRIDIS.len() {
+        let offset = i as f64 / (VIRIDIS.len() - 1) as f64;
+        let _ = writeln!(
+            gradient,
+            "      <stop offset=\"{}\" stop-color=\"{}\"/>",
+            px(offset),
+            colormap(offset)
+        );
+    }
+    gradient.push_str("    </linearGradient>\n");
+    let mut svg = open_svg(options, &frame, &gradient);
+
+    let _ = writeln!(svg, "  <g clip-path=\"url(#histr-plot-area)\">");
+    for i in 0..x_axis.len() {
+        let left = x_scale.map(x_edges[i]);
+        let right = x_scale.map(x_edges[i + 1]);
+        for j in 0..y_axis.len() {
+            let value = values[i * y_axis.len() + j];
+            if !visible(&value) {
+                continue;
+            }
+            let bottom = y_scale.map(y_edges[j]);
+            let top = y_scale.map(y_edges[j + 1]);
+            let _ = writeln!(
+                svg,
+                "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                px(left),
+                px(top),
+                px(right - left),
+                px(bottom - top),
+                colormap(z_scale.map(value))
+            );
+        }
+    }
+    let _ = writeln!(svg, "  </g>");
+    write_axes(&mut svg, &frame, &x_scale, &y_scale, options);
+
+    let bar_left = frame.right + 15.0;
+    let bar_right = bar_left + COLORBAR_WIDTH;
+    let _ = writeln!(svg, "  <g class=\"colorbar\">");
+    let _ = writeln!(
+        svg,
+        "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"url(#histr-colormap)\" stroke=\"black\"/>",
+        px(bar_left),
+        px(frame.top),
+        px(COLORBAR_WIDTH),
+        px(frame.bottom - frame.top)
+    );
+    let colorbar_scale = frame.y_scale(z_range, options.log_z);
+    for (value, label) in colorbar_scale.ticks() {
+        let y = colorbar_scale.map(value);
+        let _ = writeln!(
+            svg,
+            "    <line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\"/>",
+            px(bar_right),
+            px(bar_right + TICK_LENGTH),
+            y = px(y)
+        );
+        let _ = writeln!(
+            svg,
+            "    <text x=\"{}\" y=\"{}\">{label}</text>",
+            px(bar_right + 8.0),
+            px(y + 4.0)
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::axis::{FixedWidthAxis, GeneralAxis};
+    use crate::storage::Weight;
+
+    // Compares with the checked-in SVG, `UPDATE_SNAPSHOTS=1` rewrites it.
+    fn assert_snapshot(name: &str, svg: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join(name);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, svg).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            svg == expected,
+            "{name} differs from the snapshot, rerun with UPDATE_SNAPSHOTS=1 to update it"
+        );
+    }
+
+    fn get_h1() -> H1<'static> {
+        H1::new(
+            Box::new(FixedWidthAxis::new(0.0, 4.0, 0.5, 8)),
+            vec![1.0, 4.0, 9.0, 16.0, 12.0, 7.0, 3.0, 0.0],
+        )
+    }
+
+    #[test]
+    fn test_linear_ticks() {
+        assert_eq!(linear_ticks(0.0, 20.0), vec![0.0, 5.0, 10.0, 15.0, 20.0]);
+        assert_eq!(linear_ticks(-1.0, 1.0), vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+        let scale = Scale::new((0.0, 15.0), false, 0.0, 1.0);
+        let labels: Vec<String> = scale.ticks().into_iter().map(|(_, label)| label).collect();
+        assert_eq!(labels, ["0.0", "2.5", "5.0", "7.5", "10.0", "12.5", "15.0"]);
+    }
+
+    #[test]
+    fn test_log_ticks() {
+        assert_eq!(log_ticks(0.1, 1000.0), vec![-1, 0, 1, 2, 3]);
+        let scale = Scale::new((2.0, 30.0), true, 0.0, 1.0);
+        let values: Vec<f64> = scale.ticks().into_iter().map(|(value, _)| value).collect();
+        assert_eq!(values, [2.0, 5.0, 10.0, 20.0]);
+    }
+
+    #[test]
+    fn test_value_range() {
+        assert_eq!(value_range([1.0, 16.0].into_iter(), false), (0.0, 17.5));
+        assert_eq!(value_range([-3.0, 16.0].into_iter(), false), (-5.0, 20.0));
+        assert_eq!(
+            value_range([0.0, 3.0, 250.0].into_iter(), true),
+            (1.0, 1000.0)
+        );
+        assert_eq!(value_range([1.0, 100.0].into_iter(), true), (0.1, 100.0));
+    }
+
+    #[test]
+    fn test_colormap() {
+        assert_eq!(colormap(0.0), "#440154");
+        assert_eq!(colormap(1.0), "#fde725");
+        assert_eq!(colormap(0.5), "#21918c");
+    }
+
+    #[test]
+    fn test_step_snapshot() {
+        let options = PlotOptions {
+            title: Some("Step <plot>".to_string()),
+            x_label: Some("x".to_string()),
+            y_label: Some("Entries".to_string()),
+            ..Default::default()
+        };
+        assert_snapshot(
+            "plot_step.svg",
+            &plot_svg(&get_h1(), PlotStyle::Step, &options),
+        );
+    }
+
+    #[test]
+    fn test_overlay_snapshot() {
+        let bars = get_h1();
+        let mut points: H1<'static, Weight> = H1::new(
+            Box::new(FixedWidthAxis::new(0.0, 4.0, 0.5, 8)),
+            vec![Weight::default(); 8],
+        );
+        points
+            .fill_weighted_many(
+                &[0.2, 0.7, 1.2, 1.3, 1.8, 2.2, 2.4, 3.1],
+                &[1.0, 2.0, 8.0, 2.0, 15.0, 10.0, 1.0, 4.0],
+            )
+            .unwrap();
+        let bars: H1<'static, Weight> = H1::new(
+            bars.axis().clone_box(),
+            bars.values()
+                .iter()
+                .map(|value| Weight {
+                    value: *value,
+                    variance: *value,
+                })
+                .collect(),
+        );
+        let layers = [
+            Layer {
+                label: Some("expected"),
+                ..Layer::new(&bars, PlotStyle::Bar)
+            },
+            Layer {
+                label: Some("observed"),
+                ..Layer::new(&points, PlotStyle::Errorbar)
+            },
+        ];
+        let options = PlotOptions {
+            log_y: true,
+            ..Default::default()
+        };
+        assert_snapshot("plot_overlay.svg", &overlay_svg(&layers, &options));
+    }
+
+    #[test]
+    fn test_heatmap_snapshot() {
+        let x_axis = FixedWidthAxis::new(0.0, 4.0, 1.0, 4);
+        let y_axis = GeneralAxis::new(vec![0.0, 1.0, 3.0, 6.0]);
+        let values: Vec<f64> = (0..12).map(|i| (i * 7 % 12) as f64).collect();
+        let options = PlotOptions {
+            title: Some("Heatmap".to_string()),
+            ..Default::default()
+        };
+        assert_snapshot(
+            "plot_heatmap.svg",
+            &heatmap_svg(&x_axis, &y_axis, &values, &options).unwrap(),
+        );
+
+        assert!(heatmap_svg(&x_axis, &y_axis, &values[1..], &options).is_err());
+    }
+}
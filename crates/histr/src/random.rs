@@ -0,0 +1,305 @@
+//! Random sampling from histograms and toy histograms, with rand.
+//!
+//! All functions take the random number generator as an argument, so that
+//! results are reproducible with a seeded generator such as
+//! `StdRng::seed_from_u64`.
+
+use rand::Rng;
+use rand_distr::{Distribution, Poisson};
+
+use crate::h1::{Flow, H1};
+use crate::storage::Storage;
+
+/// Where in the selected bin a sampled value is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Position {
+    /// Uniformly distributed within the bin.
+    #[default]
+    Uniform,
+    /// At the bin center.
+    Center,
+}
+
+/// Sampler of values distributed like the bin contents of a histogram.
+///
+/// Bins are selected with an alias table (Vose's method), built once so that
+/// each value takes constant time. Flow bins are not sampled.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    edges: Vec<f64>,
+    // Probability of keeping the bin, scaled so that the mean is 1
+    thresholds: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl Sampler {
+    pub fn new<S: Storage>(h1: &H1<'_, S>) -> Result<Self, &'static str> {
+        let values = h1.values();
+        if values
+            .iter()
+            .any(|value| !(value.is_finite() && *value >= 0.0))
+        {
+            return Err("Bin contents must be finite and not negative.");
+        }
+        let total: f64 = values.iter().sum();
+        if total == 0.0 {
+            return Err("Histogram must not be empty.");
+        }
+
+        let n = values.len();
+        let mut thresholds: Vec<f64> = values
+            .iter()
+            .map(|value| value * n as f64 / total)
+            .collect();
+        let mut aliases: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| thresholds[i] < 1.0);
+        while let (Some(&i), Some(&j)) = (small.last(), large.last()) {
+            small.pop();
+            aliases[i] = j;
+            thresholds[j] -= 1.0 - thresholds[i];
+            if thresholds[j] < 1.0 {
+                large.pop();
+                small.push(j);
+            }
+        }
+        // Left over by rounding, these are kept with probability 1
+        for i in small.into_iter().chain(large) {
+            thresholds[i] = 1.0;
+        }
+        Ok(Sampler {
+            edges: h1.axis().bin_edges().into_owned(),
+            thresholds,
+            aliases,
+        })
+    }
+
+    /// Index of a random bin.
+    pub fn sample_bin<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let i = rng.random_range(0..self.thresholds.len());
+        if rng.random::<f64>() < self.thresholds[i] {
+            i
+        } else {
+            self.aliases[i]
+        }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R, position: Position) -> f64 {
+        let bin = self.sample_bin(rng);
+        let (lower, upper) = (self.edges[bin], self.edges[bin + 1]);
+        match position {
+            Position::Uniform => lower + rng.random::<f64>() * (upper - lower),
+            Position::Center => (lower + upper) / 2.0,
+        }
+    }
+
+    pub fn sample_many<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        n: usize,
+        position: Position,
+    ) -> Vec<f64> {
+        (0..n).map(|_| self.sample(rng, position)).collect()
+    }
+}
+
+// Poisson random number with the given mean, zero for a mean of zero.
+fn poisson<R: Rng + ?Sized>(rng: &mut R, mean: f64) -> Result<f64, &'static str> {
+    if mean == 0.0 {
+        return Ok(0.0);
+    }
+    Poisson::new(mean)
+        .map(|distribution| distribution.sample(rng))
+        .map_err(|_| "Contents must be finite and not negative.")
+}
+
+// Poisson bootstrap of a bin: the number of entries with the mean weight is
+// fluctuated, the number being the effective number of entries.
+fn bootstrap<R: Rng + ?Sized>(
+    rng: &mut R,
+    value: f64,
+    variance: f64,
+) -> Result<Flow, &'static str> {
+    if value == 0.0 || variance == 0.0 {
+        return Ok(Flow { value, variance });
+    }
+    let mean_weight = variance / value;
+    let entries = poisson(rng, value / mean_weight)?;
+    Ok(Flow {
+        value: entries * mean_weight,
+        variance: entries * mean_weight * mean_weight,
+    })
+}
+
+impl<S: Storage> H1<'_, S> {
+    /// Sampler of values distributed like the bin contents.
+    pub fn sampler(&self) -> Result<Sampler, &'static str> {
+        Sampler::new(self)
+    }
+
+    /// `n` random values distributed like the bin contents.
+    pub fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        n: usize,
+        position: Position,
+    ) -> Result<Vec<f64>, &'static str> {
+        Ok(self.sampler()?.sample_many(rng, n, position))
+    }
+
+    /// Toy histogram with Poisson-distributed contents, the contents of this
+    /// histogram (including flows) being the expected values.
+    pub fn poisson_toy<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<H1<'static>, &'static str> {
+        let values = self
+            .values()
+            .iter()
+            .map(|value| poisson(rng, *value))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut toy = H1::new(self.axis().clone_box(), values);
+        let underflow = poisson(rng, self.underflow().value)?;
+        toy.set_underflow(Flow {
+            value: underflow,
+            variance: underflow,
+        });
+        let overflow = poisson(rng, self.overflow().value)?;
+        toy.set_overflow(Flow {
+            value: overflow,
+            variance: overflow,
+        });
+        Ok(toy)
+    }
+
+    /// Bootstrap replica of the filled sample (Poisson bootstrap).
+    ///
+    /// Each bin holds its effective number of entries with the mean weight of
+    /// the bin; the number of entries is fluctuated with a Poisson
+    /// distribution. For unweighted histograms this resamples the entries
+    /// with replacement, with a fluctuating total.
+    pub fn bootstrap<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<H1<'static>, &'static str> {
+        let bins = self
+            .values()
+            .iter()
+            .zip(self.variances().iter())
+            .map(|(value, variance)| bootstrap(rng, *value, *variance))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut replica = H1::new(
+            self.axis().clone_box(),
+            bins.iter().map(|bin| bin.value).collect(),
+        );
+        replica.set_variances(Some(bins.iter().map(|bin| bin.variance).collect()))?;
+        let underflow = self.underflow();
+        replica.set_underflow(bootstrap(rng, underflow.value, underflow.variance)?);
+        let overflow = self.overflow();
+        replica.set_overflow(bootstrap(rng, overflow.value, overflow.variance)?);
+        Ok(replica)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::axis::GeneralAxis;
+
+    fn h1(values: &[f64]) -> H1<'static> {
+        H1::new(
+            Box::new(GeneralAxis::new(vec![0.0, 1.0, 2.0, 4.0, 8.0])),
+            values.to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_sample_bins() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let sampler = h1(&[1.0, 0.0, 3.0, 4.0]).sampler().unwrap();
+        let mut counts = [0usize; 4];
+        for _ in 0..80_000 {
+            counts[sampler.sample_bin(&mut rng)] += 1;
+        }
+        // Expected 10000, 0, 30000 and 40000 with standard deviations below 200
+        assert!(counts[0].abs_diff(10_000) < 1000, "{counts:?}");
+        assert_eq!(counts[1], 0);
+        assert!(counts[2].abs_diff(30_000) < 1000, "{counts:?}");
+        assert!(counts[3].abs_diff(40_000) < 1000, "{counts:?}");
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let source = h1(&[0.0, 0.0, 0.0, 1.0]);
+        let values = source.sample(&mut rng, 1000, Position::Uniform).unwrap();
+        assert!(values.iter().all(|value| (4.0..8.0).contains(value)));
+        let mean = values.iter().sum::<f64>() / 1000.0;
+        assert!((mean - 6.0).abs() < 0.2, "{mean}");
+
+        let values = h1(&[1.0, 1.0, 0.0, 0.0])
+            .sample(&mut rng, 100, Position::Center)
+            .unwrap();
+        assert!(values.iter().all(|value| *value == 0.5 || *value == 1.5));
+
+        // Same seed, same values
+        let first = source
+            .sample(&mut StdRng::seed_from_u64(3), 10, Position::Uniform)
+            .unwrap();
+        let second = source
+            .sample(&mut StdRng::seed_from_u64(3), 10, Position::Uniform)
+            .unwrap();
+        assert_eq!(first, second);
+
+        assert!(h1(&[0.0; 4]).sampler().is_err());
+        assert!(h1(&[1.0, -1.0, 0.0, 0.0]).sampler().is_err());
+    }
+
+    #[test]
+    fn test_poisson_toy() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut source = h1(&[100.0, 0.0, 2.5, 400.0]);
+        source.set_overflow(Flow {
+            value: 50.0,
+            variance: 50.0,
+        });
+        let mut sums = [0.0; 4];
+        for _ in 0..1000 {
+            let toy = source.poisson_toy(&mut rng).unwrap();
+            assert!(!toy.has_variances());
+            assert_eq!(toy.values()[1], 0.0);
+            assert!(toy.values().iter().all(|value| value.fract() == 0.0));
+            for (sum, value) in sums.iter_mut().zip(toy.values().iter()) {
+                *sum += value;
+            }
+        }
+        // Means of 1000 toys, within about four standard deviations
+        assert!((sums[0] / 1000.0 - 100.0).abs() < 1.3);
+        assert!((sums[2] / 1000.0 - 2.5).abs() < 0.2);
+        assert!((sums[3] / 1000.0 - 400.0).abs() < 2.6);
+        assert!(source.poisson_toy(&mut rng).unwrap().overflow().value > 0.0);
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let mut rng = StdRng::seed_from_u64(5);
+        // The last bin has 25 entries of weight 4
+        let mut source = h1(&[10.0, 0.0, 20.0, 100.0]);
+        source
+            .set_variances(Some(vec![10.0, 0.0, 20.0, 400.0]))
+            .unwrap();
+        let mut sum = 0.0;
+        let mut sum_of_squares = 0.0;
+        for _ in 0..2000 {
+            let replica = source.bootstrap(&mut rng).unwrap();
+            let value = replica.values()[3];
+            assert_eq!(value % 4.0, 0.0);
+            assert_eq!(replica.variances()[3], 4.0 * value);
+            assert_eq!(replica.values()[1], 0.0);
+            sum += value;
+            sum_of_squares += value * value;
+        }
+        // Mean 100 and variance 400, as the variance of the source bin
+        let mean = sum / 2000.0;
+        let variance = sum_of_squares / 2000.0 - mean * mean;
+        assert!((mean - 100.0).abs() < 2.0, "{mean}");
+        assert!((variance - 400.0).abs() < 60.0, "{variance}");
+    }
+}
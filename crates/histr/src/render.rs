@@ -0,0 +1,472 @@
+//! Text (terminal) and HTML rendering of histograms as bar charts.
+//!
+//! Horizontal charts have one row per bin, labelled with the bin edges.
+//! Vertical charts have one column per bin. Bars are drawn with Unicode block
+//! characters (with eighths of a cell resolution) or with ASCII `#`.
+//! Non-positive values are drawn as empty bars.
+
+use std::fmt::{self, Display, Write};
+use std::io::IsTerminal;
+
+use crate::h1::H1;
+use crate::storage::Storage;
+
+const EIGHTHS: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+const VERTICAL_EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+const BAR_COLOR: &str = "\x1b[36m";
+const ERROR_COLOR: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+/// When to use ANSI color codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// If the standard output is a terminal and `NO_COLOR` is not set.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(&self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextOptions {
+    pub orientation: Orientation,
+    /// Length of the longest bar (horizontal) or width of the plot area (vertical).
+    pub width: usize,
+    /// Number of rows of vertical charts.
+    pub height: usize,
+    pub log_scale: bool,
+    /// Draw error bars (square roots of the variances).
+    pub errors: bool,
+    /// Use Unicode block characters, otherwise only ASCII.
+    pub unicode: bool,
+    pub color: ColorChoice,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            orientation: Orientation::Horizontal,
+            width: 50,
+            height: 12,
+            log_scale: false,
+            errors: false,
+            unicode: true,
+            color: ColorChoice::Auto,
+        }
+    }
+}
+
+impl TextOptions {
+    pub fn vertical() -> Self {
+        TextOptions {
+            orientation: Orientation::Vertical,
+            width: 60,
+            ..Default::default()
+        }
+    }
+}
+
+// Maps values to bar lengths in cells (fractional).
+struct Scale {
+    log_scale: bool,
+    min: f64,
+    max: f64,
+    length: f64,
+}
+
+impl Scale {
+    fn new(values: &[f64], log_scale: bool, length: usize) -> Self {
+        let max = values.iter().copied().fold(0.0, f64::max);
+        let min = if log_scale {
+            values
+                .iter()
+                .copied()
+                .filter(|value| *value > 0.0)
+                .fold(f64::INFINITY, f64::min)
+        } else {
+            0.0
+        };
+        Scale {
+            log_scale,
+            min,
+            max,
+            length: length as f64,
+        }
+    }
+
+    fn cells(&self, value: f64) -> f64 {
+        if value <= 0.0 || value.is_nan() || self.max <= 0.0 {
+            return 0.0;
+        }
+        let value = value.min(self.max);
+        if !self.log_scale {
+            return value / self.max * self.length;
+        }
+        if self.max <= self.min {
+            return self.length;
+        }
+        // The smallest positive value still gets one cell
+        let fraction = (value / self.min).ln() / (self.max / self.min).ln();
+        (1.0 + fraction * (self.length - 1.0)).max(1.0)
+    }
+}
+
+/// Number of decimals needed to show all edges.
+pub(crate) fn edge_precision(edges: &[f64]) -> usize {
+    (0..=10)
+        .find(|precision| {
+            let factor = 10f64.powi(*precision as i32);
+            edges.iter().all(|edge| {
+                ((edge * factor).round() / factor - edge).abs() <= 1e-9 * edge.abs().max(1.0)
+            })
+        })
+        .unwrap_or(10)
+}
+
+pub(crate) fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.0}")
+    } else if value != 0.0 && (value.abs() < 1e-3 || value.abs() >= 1e6) {
+        format!("{value:.3e}")
+    } else {
+        let text = format!("{value:.4}");
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+// Horizontal bar of a fractional number of cells.
+fn horizontal_bar(cells: f64, unicode: bool) -> String {
+    if unicode {
+        let eighths = (cells * 8.0).round() as usize;
+        let mut bar = "█".repeat(eighths / 8);
+        if !eighths.is_multiple_of(8) {
+            bar.push(EIGHTHS[eighths % 8]);
+        }
+        bar
+    } else {
+        "#".repeat(cells.round() as usize)
+    }
+}
+
+fn paint(text: &str, color: &str, enabled: bool) -> String {
+    if enabled && !text.is_empty() {
+        format!("{color}{text}{RESET}")
+    } else {
+        text.to_string()
+    }
+}
+
+// Interval of a bin, closed on the right only for the last bin.
+fn bin_label(edges: &[f64], i: usize, precision: usize) -> String {
+    let close = if i == edges.len() - 2 { ']' } else { ')' };
+    format!(
+        "[{:.p$}, {:.p$}{close}",
+        edges[i],
+        edges[i + 1],
+        p = precision
+    )
+}
+
+fn render_horizontal(
+    values: &[f64],
+    errors: Option<&[f64]>,
+    edges: &[f64],
+    options: &TextOptions,
+) -> String {
+    let color = options.color.enabled();
+    let upper_values: Vec<f64> = match errors {
+        Some(errors) => values.iter().zip(errors).map(|(v, e)| v + e).collect(),
+        None => values.to_vec(),
+    };
+    let scale = Scale::new(&upper_values, options.log_scale, options.width);
+    let precision = edge_precision(edges);
+    let labels: Vec<String> = (0..values.len())
+        .map(|i| bin_label(edges, i, precision))
+        .collect();
+    let label_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (i, label) in labels.iter().enumerate() {
+        let bar_cells = scale.cells(values[i]);
+        let bar = horizontal_bar(bar_cells, options.unicode);
+        let mut drawn = bar.chars().count();
+        let mut whisker = String::new();
+        let mut number = format_value(values[i]);
+        if let Some(errors) = errors {
+            let end = scale.cells(upper_values[i]).round() as usize;
+            if end > drawn {
+                let (line, cap) = if options.unicode {
+                    ('─', '┤')
+                } else {
+                    ('-', '|')
+                };
+                whisker = std::iter::repeat_n(line, end - drawn - 1).collect();
+                whisker.push(cap);
+            }
+            drawn += whisker.chars().count();
+            number = format!("{number} ± {}", format_value(errors[i]));
+        }
+        let padding = " ".repeat(options.width + 1 - drawn.min(options.width));
+        let _ = writeln!(
+            text,
+            "{label:>label_width$} {}{}{padding}{number}",
+            paint(&bar, BAR_COLOR, color),
+            paint(&whisker, ERROR_COLOR, color)
+        );
+    }
+    text
+}
+
+fn render_vertical(
+    values: &[f64],
+    errors: Option<&[f64]>,
+    edges: &[f64],
+    options: &TextOptions,
+) -> String {
+    let color = options.color.enabled();
+    let upper_values: Vec<f64> = match errors {
+        Some(errors) => values.iter().zip(errors).map(|(v, e)| v + e).collect(),
+        None => values.to_vec(),
+    };
+    let height = options.height.max(1);
+    let scale = Scale::new(&upper_values, options.log_scale, height);
+    let column_width = (options.width / values.len()).max(1);
+    let top_label = format_value(scale.max);
+    let label_width = top_label.len().max(1);
+
+    let mut text = String::new();
+    for row in (0..height).rev() {
+        let label = if row == height - 1 {
+            top_label.as_str()
+        } else if row == 0 && !options.log_scale {
+            "0"
+        } else {
+            ""
+        };
+        let mut line = String::new();
+        for (value, upper) in values.iter().zip(&upper_values) {
+            let cells = scale.cells(*value) - row as f64;
+            let error_top = if errors.is_some() {
+                scale.cells(*upper).round()
+            } else {
+                0.0
+            };
+            let (symbol, symbol_color) = if cells >= 1.0 {
+                (if options.unicode { '█' } else { '#' }, BAR_COLOR)
+            } else if cells > 0.0 && options.unicode && (cells * 8.0).round() > 0.0 {
+                (VERTICAL_EIGHTHS[(cells * 8.0).round() as usize], BAR_COLOR)
+            } else if cells > 0.0 && !options.unicode && cells >= 0.5 {
+                ('#', BAR_COLOR)
+            } else if (row as f64) < error_top && (row as f64) >= scale.cells(*value).floor() {
+                (if options.unicode { '│' } else { '|' }, ERROR_COLOR)
+            } else {
+                (' ', "")
+            };
+            let column: String = std::iter::repeat_n(symbol, column_width).collect();
+            line.push_str(&paint(&column, symbol_color, color && symbol != ' '));
+        }
+        let _ = writeln!(text, "{label:>label_width$} {}", line.trim_end());
+    }
+
+    let axis_line = if options.unicode { "─" } else { "-" };
+    let plot_width = column_width * values.len();
+    let _ = writeln!(text, "{:label_width$} {}", "", axis_line.repeat(plot_width));
+    let precision = edge_precision(edges);
+    let min_label = format!("{:.p$}", edges[0], p = precision);
+    let max_label = format!("{:.p$}", edges[edges.len() - 1], p = precision);
+    let gap = plot_width
+        .saturating_sub(min_label.len() + max_label.len())
+        .max(1);
+    let _ = writeln!(
+        text,
+        "{:label_width$} {min_label}{}{max_label}",
+        "",
+        " ".repeat(gap)
+    );
+    text
+}
+
+/// Bar chart of the histogram as text.
+pub fn render_text<S: Storage>(h1: &H1<'_, S>, options: &TextOptions) -> String {
+    if h1.is_empty() {
+        return String::new();
+    }
+    let values = h1.values();
+    let variances = h1.variances();
+    let errors: Option<Vec<f64>> = options
+        .errors
+        .then(|| variances.iter().map(|variance| variance.sqrt()).collect());
+    let edges = h1.axis().bin_edges();
+    match options.orientation {
+        Orientation::Horizontal => render_horizontal(&values, errors.as_deref(), &edges, options),
+        Orientation::Vertical => render_vertical(&values, errors.as_deref(), &edges, options),
+    }
+}
+
+/// HTML table with one row per bin and bars drawn as inline blocks (e.g. for notebooks).
+pub fn render_html<S: Storage>(h1: &H1<'_, S>) -> String {
+    let values = h1.values();
+    let variances = h1.variances();
+    let edges = h1.axis().bin_edges();
+    let precision = edge_precision(&edges);
+    let max = values.iter().copied().fold(0.0, f64::max);
+
+    let mut html = String::from(
+        "<table class=\"histr\"><thead><tr><th>bin</th><th>value</th><th>error</th><th></th></tr></thead><tbody>",
+    );
+    for (i, value) in values.iter().enumerate() {
+        let percent = if max > 0.0 && *value > 0.0 {
+            value / max * 100.0
+        } else {
+            0.0
+        };
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td>\
+             <td style=\"width:20em\"><div style=\"background:#4c9be8;height:1em;width:{percent:.2}%\"></div></td></tr>",
+            bin_label(&edges, i, precision),
+            format_value(*value),
+            format_value(variances[i].sqrt()),
+        );
+    }
+    html.push_str("</tbody></table>");
+    html
+}
+
+/// Horizontal bar chart with default options, without colors as the text may
+/// not end up in a terminal; use `render_text` for colors.
+impl<S: Storage> Display for H1<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = TextOptions {
+            color: ColorChoice::Never,
+            ..Default::default()
+        };
+        f.write_str(&render_text(self, &options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::GeneralAxis;
+
+    fn get_h1() -> H1<'static> {
+        H1::new(
+            Box::new(GeneralAxis::new(vec![0.0, 0.5, 1.0, 2.0])),
+            vec![4.0, 1.0, 0.0],
+        )
+    }
+
+    fn options() -> TextOptions {
+        TextOptions {
+            width: 8,
+            color: ColorChoice::Never,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_horizontal() {
+        let text = render_text(&get_h1(), &options());
+        let expected = "\
+[0.0, 0.5) ████████ 4
+[0.5, 1.0) ██       1
+[1.0, 2.0]          0
+";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn test_ascii_errors() {
+        let options = TextOptions {
+            unicode: false,
+            errors: true,
+            ..options()
+        };
+        let text = render_text(&get_h1(), &options);
+        let expected = "\
+[0.0, 0.5) #####--| 4 ± 2
+[0.5, 1.0) #-|      1 ± 1
+[1.0, 2.0]          0 ± 0
+";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn test_log_scale() {
+        let options = TextOptions {
+            log_scale: true,
+            ..options()
+        };
+        let text = render_text(&get_h1(), &options);
+        assert!(text.starts_with("[0.0, 0.5) ████████ 4\n[0.5, 1.0) █ "));
+    }
+
+    #[test]
+    fn test_vertical() {
+        let options = TextOptions {
+            orientation: Orientation::Vertical,
+            width: 6,
+            height: 4,
+            ..options()
+        };
+        let text = render_text(&get_h1(), &options);
+        let expected = "\
+4 ██
+  ██
+  ██
+0 ████
+  ──────
+  0.0 2.0
+";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn test_color() {
+        let options = TextOptions {
+            color: ColorChoice::Always,
+            ..options()
+        };
+        assert!(render_text(&get_h1(), &options).contains(BAR_COLOR));
+    }
+
+    #[test]
+    fn test_display_without_color() {
+        let text = get_h1().to_string();
+        assert!(!text.contains('\x1b'));
+        assert!(text.starts_with("[0.0, 0.5) "));
+    }
+
+    #[test]
+    fn test_html() {
+        let html = render_html(&get_h1());
+        assert!(html.starts_with("<table"));
+        assert!(html.contains("<td>[0.5, 1.0)</td><td>1</td><td>1</td>"));
+        // Closed like the last bin of the text chart
+        assert!(html.contains("<td>[1.0, 2.0]</td><td>0</td>"));
+        assert!(html.contains("width:25.00%"));
+    }
+}
@@ -0,0 +1,1079 @@
+//! Reading and writing histograms as `TH1D` / `TH1F` objects in ROOT files.
+//!
+//! Only the small subset of the ROOT file format needed for one-dimensional
+//! histograms is implemented. Files are written uncompressed with a single
+//! top-level directory; on reading, uncompressed and zlib-compressed objects
+//! are supported. All numbers in ROOT files are big-endian.
+//!
+//! Flow bins map to ROOT's bins `0` and `nbins + 1`, variances to `fSumw2`.
+//!
+//! Limitation: the `StreamerInfo` list of written files is empty, i.e. it
+//! does not describe the layout of `TH1D` and its base classes. Readers with
+//! built-in definitions of `TH1D` (version 3) do not need it; ROOT may warn
+//! about the missing records, and readers that rely on them cannot read the
+//! histogram. Malformed input gives an error on reading, never a panic.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::read::ZlibDecoder;
+
+use crate::axis::{Axis, FixedWidthAxis, GeneralAxis};
+use crate::h1::{Flow, H1};
+
+const ROOT_VERSION: i32 = 63000;
+const BEGIN: usize = 100;
+const KEY_VERSION: i16 = 4;
+const BIG_KEY_VERSION: i16 = 1000;
+const BYTE_COUNT_MASK: u32 = 0x4000_0000;
+const OBJECT_BITS: u32 = 0x0300_0000;
+
+const TH1D_VERSION: i16 = 3;
+const TH1_VERSION: i16 = 8;
+const TNAMED_VERSION: i16 = 1;
+const TOBJECT_VERSION: i16 = 1;
+const TAXIS_VERSION: i16 = 10;
+const TATTAXIS_VERSION: i16 = 4;
+const TATT_VERSION: i16 = 2;
+const TLIST_VERSION: i16 = 5;
+const TDIRECTORY_VERSION: i16 = 5;
+
+/// Write a histogram as a `TH1D` named `name` into a new ROOT file.
+pub fn save_root(h1: &H1, path: impl AsRef<Path>, name: &str) -> Result<(), Box<dyn Error>> {
+    let file_name = path
+        .as_ref()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("histr.root")
+        .to_string();
+    let mut file = File::create(path)?;
+    file.write_all(&write_root(h1, &file_name, name))?;
+    Ok(())
+}
+
+/// Read a `TH1D` or `TH1F` named `name` from a ROOT file.
+pub fn load_root(path: impl AsRef<Path>, name: &str) -> Result<H1<'static>, Box<dyn Error>> {
+    let mut data = Vec::new();
+    File::open(path)?.read_to_end(&mut data)?;
+    read_root(&data, name)
+}
+
+/// Names and class names of all objects in the top directory.
+pub fn list_keys(data: &[u8]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    Ok(read_keys(data)?
+        .into_iter()
+        .map(|key| (key.name, key.class_name))
+        .collect())
+}
+
+pub fn read_root(data: &[u8], name: &str) -> Result<H1<'static>, Box<dyn Error>> {
+    let key = read_keys(data)?
+        .into_iter()
+        .filter(|key| key.name == name)
+        .max_by_key(|key| key.cycle)
+        .ok_or("Object not found in the ROOT file.")?;
+    let content_type = match key.class_name.as_str() {
+        "TH1D" => ContentType::Double,
+        "TH1F" => ContentType::Float,
+        _ => return Err("Only TH1D and TH1F objects are supported.".into()),
+    };
+    let object = read_object(data, &key)?;
+    read_th1(&mut Cursor::new(&object), content_type)
+}
+
+// ----- Writing -----
+
+pub fn write_root(h1: &H1, file_name: &str, name: &str) -> Vec<u8> {
+    write_root_at(h1, file_name, name, datime_now(), uuid_now())
+}
+
+// Writing with the given modification time and file UUID.
+fn write_root_at(h1: &H1, file_name: &str, name: &str, datime: u32, uuid: u128) -> Vec<u8> {
+    let title = h1
+        .metadata()
+        .get("title")
+        .map_or("", |title| title.as_str());
+
+    let mut object = Buffer::default();
+    write_th1d(&mut object, h1, name, title);
+    let mut streamer_info = Buffer::default();
+    write_empty_tlist(&mut streamer_info);
+
+    // Layout: header, top directory, histogram, streamer info, keys list, free segments.
+    let directory_key_len = key_len("TFile", file_name, "");
+    let names_len = tstring_len(file_name) + tstring_len("");
+    let directory_len = directory_key_len + names_len + 60;
+    let object_seek = BEGIN + directory_len;
+    let object_key = Key {
+        class_name: "TH1D".into(),
+        name: name.into(),
+        title: title.into(),
+        object_len: object.0.len(),
+        seek: object_seek,
+        cycle: 1,
+        datime,
+    };
+    let info_seek = object_seek + object_key.key_len() + object.0.len();
+    let info_key = Key {
+        class_name: "TList".into(),
+        name: "StreamerInfo".into(),
+        title: "Doubly linked list".into(),
+        object_len: streamer_info.0.len(),
+        seek: info_seek,
+        cycle: 1,
+        datime,
+    };
+    let keys_seek = info_seek + info_key.key_len() + streamer_info.0.len();
+    let mut keys_list = Buffer::default();
+    keys_list.i32(1);
+    object_key.write_header(&mut keys_list);
+    let keys_key = Key {
+        class_name: "TFile".into(),
+        name: file_name.into(),
+        title: "".into(),
+        object_len: keys_list.0.len(),
+        seek: keys_seek,
+        cycle: 1,
+        datime,
+    };
+    let free_seek = keys_seek + keys_key.key_len() + keys_list.0.len();
+    let free_key = Key {
+        class_name: "TFile".into(),
+        name: file_name.into(),
+        title: "".into(),
+        object_len: 10,
+        seek: free_seek,
+        cycle: 1,
+        datime,
+    };
+    let end = free_seek + free_key.key_len() + 10;
+
+    let mut file = Buffer::default();
+    // File header
+    file.bytes(b"root");
+    file.i32(ROOT_VERSION);
+    file.i32(BEGIN as i32);
+    file.i32(end as i32);
+    file.i32(free_seek as i32);
+    file.i32((free_key.key_len() + 10) as i32);
+    file.i32(1); // Number of free segments
+    file.i32((directory_key_len + names_len) as i32);
+    file.u8(4); // Units (bytes per seek)
+    file.i32(0); // Compression
+    file.i32(info_seek as i32);
+    file.i32((info_key.key_len() + streamer_info.0.len()) as i32);
+    write_uuid(&mut file, uuid);
+    file.0.resize(BEGIN, 0);
+
+    // Top directory
+    let directory_key = Key {
+        class_name: "TFile".into(),
+        name: file_name.into(),
+        title: "".into(),
+        object_len: names_len + 60,
+        seek: BEGIN,
+        cycle: 1,
+        datime,
+    };
+    directory_key.write_header(&mut file);
+    file.tstring(file_name);
+    file.tstring("");
+    file.i16(TDIRECTORY_VERSION);
+    file.u32(datime);
+    file.u32(datime);
+    file.i32((keys_key.key_len() + keys_list.0.len()) as i32);
+    file.i32((directory_key_len + names_len) as i32);
+    file.i32(BEGIN as i32); // Seek of this directory
+    file.i32(0); // Seek of the parent
+    file.i32(keys_seek as i32);
+    write_uuid(&mut file, uuid);
+    file.0.extend([0; 12]);
+
+    object_key.write_header(&mut file);
+    file.bytes(&object.0);
+    info_key.write_header(&mut file);
+    file.bytes(&streamer_info.0);
+    keys_key.write_header(&mut file);
+    file.bytes(&keys_list.0);
+    free_key.write_header(&mut file);
+    file.i16(1);
+    file.i32(end as i32);
+    file.i32(2_000_000_000);
+    file.0
+}
+
+fn write_th1d(buffer: &mut Buffer, h1: &H1, name: &str, title: &str) {
+    let n_bins = h1.len();
+    let (underflow, overflow) = (h1.underflow(), h1.overflow());
+    let with_flow = |values: &[f64], underflow: f64, overflow: f64| {
+        let mut result = vec![underflow];
+        result.extend_from_slice(values);
+        result.push(overflow);
+        result
+    };
+    let contents = with_flow(h1.bin_contents(), underflow.value, overflow.value);
+
+    // Statistics from bin centers, like TH1::ResetStats
+    let edges = h1.axis().bin_edges();
+    let (mut sumw, mut sumw2, mut sumwx, mut sumwx2) = (0.0, 0.0, 0.0, 0.0);
+    for (i, (content, variance)) in h1
+        .bin_contents()
+        .iter()
+        .zip(h1.variances().iter())
+        .enumerate()
+    {
+        let center = (edges[i] + edges[i + 1]) / 2.0;
+        sumw += content;
+        sumw2 += variance;
+        sumwx += content * center;
+        sumwx2 += content * center * center;
+    }
+
+    let th1d = buffer.begin_object(TH1D_VERSION);
+    let th1 = buffer.begin_object(TH1_VERSION);
+    write_tnamed(buffer, name, title);
+    let att_line = buffer.begin_object(TATT_VERSION);
+    buffer.i16(602);
+    buffer.i16(1);
+    buffer.i16(1);
+    buffer.end_object(att_line);
+    let att_fill = buffer.begin_object(TATT_VERSION);
+    buffer.i16(0);
+    buffer.i16(1001);
+    buffer.end_object(att_fill);
+    let att_marker = buffer.begin_object(TATT_VERSION);
+    buffer.i16(1);
+    buffer.i16(1);
+    buffer.f32(1.0);
+    buffer.end_object(att_marker);
+    buffer.i32((n_bins + 2) as i32);
+    write_taxis(buffer, "xaxis", h1.axis());
+    write_taxis(buffer, "yaxis", &FixedWidthAxis::new(0.0, 1.0, 1.0, 1));
+    write_taxis(buffer, "zaxis", &FixedWidthAxis::new(0.0, 1.0, 1.0, 1));
+    buffer.i16(0); // Bar offset
+    buffer.i16(1000); // Bar width
+    buffer.f64(sumw + underflow.value + overflow.value); // Entries
+    buffer.f64(sumw);
+    buffer.f64(sumw2);
+    buffer.f64(sumwx);
+    buffer.f64(sumwx2);
+    buffer.f64(-1111.0); // Maximum
+    buffer.f64(-1111.0); // Minimum
+    buffer.f64(0.0); // Norm factor
+    buffer.f64s(&[]); // Contour
+    let weighted_flow =
+        underflow.variance != underflow.value || overflow.variance != overflow.value;
+    if h1.has_variances() || weighted_flow {
+        buffer.f64s(&with_flow(
+            &h1.variances(),
+            underflow.variance,
+            overflow.variance,
+        ));
+    } else {
+        buffer.f64s(&[]);
+    }
+    buffer.tstring(""); // Option
+    write_empty_tlist(buffer); // Functions
+    buffer.i32(0); // Buffer size
+    buffer.u8(0); // Empty buffer array
+    buffer.i32(0); // Bin error option
+    buffer.i32(2); // Statistics overflows (neutral)
+    buffer.end_object(th1);
+    buffer.f64s(&contents);
+    buffer.end_object(th1d);
+}
+
+fn write_taxis(buffer: &mut Buffer, name: &str, axis: &dyn Axis) {
+    let taxis = buffer.begin_object(TAXIS_VERSION);
+    write_tnamed(buffer, name, "");
+    let att_axis = buffer.begin_object(TATTAXIS_VERSION);
+    buffer.i32(510); // Divisions
+    buffer.i16(1); // Axis color
+    buffer.i16(1); // Label color
+    buffer.i16(42); // Label font
+    buffer.f32(0.005); // Label offset
+    buffer.f32(0.035); // Label size
+    buffer.f32(0.03); // Tick length
+    buffer.f32(1.0); // Title offset
+    buffer.f32(0.035); // Title size
+    buffer.i16(1); // Title color
+    buffer.i16(42); // Title font
+    buffer.end_object(att_axis);
+    buffer.i32(axis.len() as i32);
+    buffer.f64(axis.min_edge());
+    buffer.f64(axis.max_edge());
+    if axis.as_any().is::<FixedWidthAxis>() {
+        buffer.f64s(&[]);
+    } else {
+        buffer.f64s(&axis.bin_edges());
+    }
+    buffer.i32(0); // First
+    buffer.i32(0); // Last
+    buffer.u16(0); // Bits
+    buffer.u8(0); // Time display
+    buffer.tstring(""); // Time format
+    buffer.u32(0); // Labels (null)
+    buffer.u32(0); // Modified labels (null)
+    buffer.end_object(taxis);
+}
+
+fn write_tnamed(buffer: &mut Buffer, name: &str, title: &str) {
+    let tnamed = buffer.begin_object(TNAMED_VERSION);
+    buffer.i16(TOBJECT_VERSION);
+    buffer.u32(0); // Unique ID
+    buffer.u32(OBJECT_BITS);
+    buffer.tstring(name);
+    buffer.tstring(title);
+    buffer.end_object(tnamed);
+}
+
+fn write_empty_tlist(buffer: &mut Buffer) {
+    let tlist = buffer.begin_object(TLIST_VERSION);
+    buffer.i16(TOBJECT_VERSION);
+    buffer.u32(0);
+    buffer.u32(OBJECT_BITS);
+    buffer.tstring("");
+    buffer.i32(0);
+    buffer.end_object(tlist);
+}
+
+fn write_uuid(buffer: &mut Buffer, uuid: u128) {
+    buffer.i16(1);
+    buffer.bytes(&uuid.to_be_bytes());
+}
+
+fn uuid_now() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos())
+}
+
+fn datime_now() -> u32 {
+    // Rough conversion of the current time to ROOT's packed TDatime
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let days = seconds / 86400;
+    let (year, month, day) = civil_from_days(days as i64);
+    let (hour, minute, second) = ((seconds / 3600) % 24, (seconds / 60) % 60, seconds % 60);
+    (((year - 1995) as u32) << 26)
+        | ((month as u32) << 22)
+        | ((day as u32) << 17)
+        | ((hour as u32) << 12)
+        | ((minute as u32) << 6)
+        | second as u32
+}
+
+// Days since 1970-01-01 to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+fn tstring_len(value: &str) -> usize {
+    if value.len() < 255 {
+        value.len() + 1
+    } else {
+        value.len() + 5
+    }
+}
+
+fn key_len(class_name: &str, name: &str, title: &str) -> usize {
+    26 + tstring_len(class_name) + tstring_len(name) + tstring_len(title)
+}
+
+#[derive(Default)]
+struct Buffer(Vec<u8>);
+
+impl Buffer {
+    fn bytes(&mut self, value: &[u8]) {
+        self.0.extend_from_slice(value);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn i16(&mut self, value: i16) {
+        self.bytes(&value.to_be_bytes());
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_be_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.bytes(&value.to_be_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_be_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes(&value.to_be_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.bytes(&value.to_be_bytes());
+    }
+
+    // TArrayD
+    fn f64s(&mut self, values: &[f64]) {
+        self.i32(values.len() as i32);
+        values.iter().for_each(|value| self.f64(*value));
+    }
+
+    fn tstring(&mut self, value: &str) {
+        if value.len() < 255 {
+            self.u8(value.len() as u8);
+        } else {
+            self.u8(255);
+            self.i32(value.len() as i32);
+        }
+        self.bytes(value.as_bytes());
+    }
+
+    // Placeholder for the byte count, followed by the class version
+    fn begin_object(&mut self, version: i16) -> usize {
+        let start = self.0.len();
+        self.u32(0);
+        self.i16(version);
+        start
+    }
+
+    fn end_object(&mut self, start: usize) {
+        let count = (self.0.len() - start - 4) as u32 | BYTE_COUNT_MASK;
+        self.0[start..start + 4].copy_from_slice(&count.to_be_bytes());
+    }
+}
+
+// ----- Reading -----
+
+#[derive(Clone, Copy)]
+enum ContentType {
+    Double,
+    Float,
+}
+
+struct Key {
+    class_name: String,
+    name: String,
+    title: String,
+    object_len: usize,
+    seek: usize,
+    cycle: i16,
+    datime: u32,
+}
+
+impl Key {
+    fn key_len(&self) -> usize {
+        key_len(&self.class_name, &self.name, &self.title)
+    }
+
+    // Header of an uncompressed key in the top directory
+    fn write_header(&self, buffer: &mut Buffer) {
+        buffer.i32((self.key_len() + self.object_len) as i32);
+        buffer.i16(KEY_VERSION);
+        buffer.i32(self.object_len as i32);
+        buffer.u32(self.datime);
+        buffer.i16(self.key_len() as i16);
+        buffer.i16(self.cycle);
+        buffer.i32(self.seek as i32);
+        buffer.i32(BEGIN as i32);
+        buffer.tstring(&self.class_name);
+        buffer.tstring(&self.name);
+        buffer.tstring(&self.title);
+    }
+}
+
+struct KeyHeader {
+    n_bytes: usize,
+    object_len: usize,
+    key_len: usize,
+    seek: usize,
+    cycle: i16,
+    class_name: String,
+    name: String,
+}
+
+// Non-negative size or position from a signed integer in the file.
+fn to_usize(value: impl TryInto<usize>) -> Result<usize, &'static str> {
+    value
+        .try_into()
+        .map_err(|_| "Invalid size or position in ROOT data.")
+}
+
+fn add(a: usize, b: usize) -> Result<usize, &'static str> {
+    a.checked_add(b)
+        .ok_or("Invalid size or position in ROOT data.")
+}
+
+fn read_key_header(cursor: &mut Cursor) -> Result<KeyHeader, &'static str> {
+    let n_bytes = to_usize(cursor.i32()?)?;
+    let version = cursor.i16()?;
+    let object_len = to_usize(cursor.i32()?)?;
+    cursor.u32()?; // Datime
+    let key_len = to_usize(cursor.i16()?)?;
+    let cycle = cursor.i16()?;
+    let seek = if version > BIG_KEY_VERSION {
+        to_usize(cursor.i64()?)?
+    } else {
+        to_usize(cursor.i32()?)?
+    };
+    if version > BIG_KEY_VERSION {
+        cursor.i64()?; // Parent directory
+    } else {
+        cursor.i32()?;
+    }
+    let class_name = cursor.tstring()?;
+    let name = cursor.tstring()?;
+    cursor.tstring()?; // Title
+    Ok(KeyHeader {
+        n_bytes,
+        object_len,
+        key_len,
+        seek,
+        cycle,
+        class_name,
+        name,
+    })
+}
+
+fn read_keys(data: &[u8]) -> Result<Vec<KeyHeader>, Box<dyn Error>> {
+    let mut cursor = Cursor::new(data);
+    if cursor.bytes(4)? != b"root" {
+        return Err("Not a ROOT file.".into());
+    }
+    let version = cursor.i32()?;
+    let begin = to_usize(cursor.i32()?)?;
+    let big_file = version >= 1_000_000;
+    if big_file {
+        cursor.i64()?; // End
+        cursor.i64()?; // Seek of free segments
+    } else {
+        cursor.i32()?;
+        cursor.i32()?;
+    }
+    cursor.i32()?; // Bytes of free segments
+    cursor.i32()?; // Number of free segments
+    let n_bytes_name = to_usize(cursor.i32()?)?;
+
+    // Top directory record
+    cursor.seek(add(begin, n_bytes_name)?)?;
+    let directory_version = cursor.i16()?;
+    cursor.skip(16)?; // Dates and sizes
+    let keys_seek = if directory_version > 1000 {
+        cursor.skip(16)?;
+        to_usize(cursor.i64()?)?
+    } else {
+        cursor.skip(8)?;
+        to_usize(cursor.i32()?)?
+    };
+    if keys_seek == 0 {
+        return Ok(Vec::new());
+    }
+
+    cursor.seek(keys_seek)?;
+    let header = read_key_header(&mut cursor)?;
+    cursor.seek(add(keys_seek, header.key_len)?)?;
+    let n_keys = to_usize(cursor.i32()?)?;
+    (0..n_keys)
+        .map(|_| Ok(read_key_header(&mut cursor)?))
+        .collect()
+}
+
+fn read_object(data: &[u8], key: &KeyHeader) -> Result<Vec<u8>, Box<dyn Error>> {
+    let start = add(key.seek, key.key_len)?;
+    let compressed = data
+        .get(start..add(key.seek, key.n_bytes)?)
+        .ok_or("Unexpected end of ROOT data.")?;
+    if compressed.len() == key.object_len {
+        return Ok(compressed.to_vec());
+    }
+
+    // Compressed blocks, each with a 9-byte header. The object length comes
+    // from the file, so it only limits the decompressed size.
+    let mut object = Vec::new();
+    let mut cursor = Cursor::new(compressed);
+    while object.len() < key.object_len {
+        let algorithm = cursor.bytes(2)?;
+        if algorithm != b"ZL" {
+            return Err("Unsupported ROOT compression algorithm.".into());
+        }
+        let sizes = cursor.bytes(7)?;
+        let compressed_size = u32::from_le_bytes([sizes[1], sizes[2], sizes[3], 0]) as usize;
+        let block = cursor.bytes(compressed_size)?;
+        let remaining = (key.object_len - object.len()) as u64;
+        ZlibDecoder::new(block)
+            .take(remaining + 1)
+            .read_to_end(&mut object)?;
+    }
+    if object.len() != key.object_len {
+        return Err("Invalid size of decompressed ROOT object.".into());
+    }
+    Ok(object)
+}
+
+fn read_th1(cursor: &mut Cursor, content_type: ContentType) -> Result<H1<'static>, Box<dyn Error>> {
+    cursor.object_header()?; // TH1D / TH1F
+    let (th1_end, th1_version) = cursor.object_header()?;
+    if th1_version < 5 {
+        return Err("Unsupported TH1 version.".into());
+    }
+    cursor.skip_object()?; // TNamed
+    cursor.skip_object()?; // TAttLine
+    cursor.skip_object()?; // TAttFill
+    cursor.skip_object()?; // TAttMarker
+    let n_cells = to_usize(cursor.i32()?)?;
+
+    let (axis_end, _) = cursor.object_header()?;
+    cursor.skip_object()?; // TNamed
+    cursor.skip_object()?; // TAttAxis
+    let n_bins = to_usize(cursor.i32()?)?;
+    let min_edge = cursor.f64()?;
+    let max_edge = cursor.f64()?;
+    let edges = cursor.f64s()?;
+    cursor.seek(axis_end)?;
+    cursor.skip_object()?; // y axis
+    cursor.skip_object()?; // z axis
+
+    if n_bins == 0 || Some(n_cells) != n_bins.checked_add(2) {
+        return Err("Inconsistent number of bins.".into());
+    }
+    let axis: Box<dyn Axis> = if edges.is_empty() {
+        if !(min_edge.is_finite() && max_edge.is_finite() && min_edge < max_edge) {
+            return Err("Invalid axis range.".into());
+        }
+        let bin_width = (max_edge - min_edge) / n_bins as f64;
+        Box::new(FixedWidthAxis::new(min_edge, max_edge, bin_width, n_bins))
+    } else if edges.len() == n_bins + 1 {
+        if edges
+            .windows(2)
+            .any(|pair| pair[0].partial_cmp(&pair[1]) != Some(Ordering::Less))
+        {
+            return Err("Bin edges must be increasing.".into());
+        }
+        Box::new(GeneralAxis::new(edges))
+    } else {
+        return Err("Inconsistent number of bin edges.".into());
+    };
+
+    cursor.skip(4)?; // Bar offset and width
+    cursor.skip(8 * 8)?; // Statistics, maximum, minimum, norm factor
+    cursor.f64s()?; // Contour
+    let sumw2 = cursor.f64s()?;
+    cursor.seek(th1_end)?;
+
+    let n = to_usize(cursor.i32()?)?;
+    let size = match content_type {
+        ContentType::Double => 8,
+        ContentType::Float => 4,
+    };
+    if n != n_cells || n > cursor.remaining() / size {
+        return Err("Inconsistent number of bins.".into());
+    }
+    let contents = match content_type {
+        ContentType::Double => (0..n)
+            .map(|_| cursor.f64())
+            .collect::<Result<Vec<_>, _>>()?,
+        ContentType::Float => (0..n)
+            .map(|_| cursor.f32().map(f64::from))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    let mut h1 = H1::new(axis, contents[1..=n_bins].to_vec());
+    let (underflow, overflow) = (contents[0], contents[n_bins + 1]);
+    if sumw2.len() == n_cells {
+        h1.set_variances(Some(sumw2[1..=n_bins].to_vec()))?;
+        h1.set_underflow(Flow {
+            value: underflow,
+            variance: sumw2[0],
+        });
+        h1.set_overflow(Flow {
+            value: overflow,
+            variance: sumw2[n_bins + 1],
+        });
+    } else {
+        h1.set_underflow(Flow {
+            value: underflow,
+            variance: underflow,
+        });
+        h1.set_overflow(Flow {
+            value: overflow,
+            variance: overflow,
+        });
+    }
+    Ok(h1)
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Cursor { data, position: 0 }
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], &'static str> {
+        let bytes = self
+            .data
+            .get(self.position..add(self.position, n)?)
+            .ok_or("Unexpected end of ROOT data.")?;
+        self.position += n;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], &'static str> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    fn seek(&mut self, position: usize) -> Result<(), &'static str> {
+        if position > self.data.len() {
+            return Err("Unexpected end of ROOT data.");
+        }
+        self.position = position;
+        Ok(())
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), &'static str> {
+        self.bytes(n).map(|_| ())
+    }
+
+    fn i16(&mut self) -> Result<i16, &'static str> {
+        Ok(i16::from_be_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, &'static str> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, &'static str> {
+        Ok(i64::from_be_bytes(self.array()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, &'static str> {
+        Ok(f32::from_be_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, &'static str> {
+        Ok(f64::from_be_bytes(self.array()?))
+    }
+
+    // TArrayD
+    fn f64s(&mut self) -> Result<Vec<f64>, &'static str> {
+        let n = self.i32()?;
+        if to_usize(n)? > self.remaining() / 8 {
+            return Err("Invalid array length.");
+        }
+        (0..n).map(|_| self.f64()).collect()
+    }
+
+    fn tstring(&mut self) -> Result<String, &'static str> {
+        let mut length = self.bytes(1)?[0] as usize;
+        if length == 255 {
+            length = to_usize(self.i32()?)?;
+        }
+        Ok(String::from_utf8_lossy(self.bytes(length)?).into_owned())
+    }
+
+    // Returns the end position and version of a streamed object
+    fn object_header(&mut self) -> Result<(usize, i16), &'static str> {
+        let byte_count = self.u32()?;
+        if byte_count & BYTE_COUNT_MASK == 0 {
+            return Err("Missing byte count in ROOT object.");
+        }
+        let end = add(self.position, (byte_count & !BYTE_COUNT_MASK) as usize)?;
+        Ok((end, self.i16()?))
+    }
+
+    fn skip_object(&mut self) -> Result<(), &'static str> {
+        let (end, _) = self.object_header()?;
+        self.seek(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    // Generated by fixtures/make_root_fixtures.py, not by ROOT
+    const TH1D_FIXED: &[u8] = include_bytes!("../fixtures/th1d_fixed.root");
+    const TH1F_VARIABLE_ZLIB: &[u8] = include_bytes!("../fixtures/th1f_variable_zlib.root");
+
+    #[test]
+    fn test_read_th1d() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            list_keys(TH1D_FIXED)?,
+            vec![("h".to_string(), "TH1D".to_string())]
+        );
+        let h1 = read_root(TH1D_FIXED, "h")?;
+
+        assert!(h1.axis().as_any().is::<FixedWidthAxis>());
+        assert_eq!(*h1.axis().bin_edges(), vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(h1.bin_contents(), &vec![1.0, 2.5, 0.0, 4.0]);
+        assert_eq!(*h1.variances(), vec![1.0, 3.25, 0.0, 8.0]);
+        assert_eq!(
+            h1.underflow(),
+            Flow {
+                value: 2.0,
+                variance: 2.0
+            }
+        );
+        assert_eq!(
+            h1.overflow(),
+            Flow {
+                value: 0.5,
+                variance: 0.25
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_compressed_th1f() -> Result<(), Box<dyn Error>> {
+        let h1 = read_root(TH1F_VARIABLE_ZLIB, "energy")?;
+
+        assert!(h1.axis().as_any().is::<GeneralAxis>());
+        assert_eq!(*h1.axis().bin_edges(), vec![0.0, 1.0, 10.0, 100.0]);
+        assert_eq!(h1.bin_contents(), &vec![3.0, 5.0, 1.0]);
+        assert!(!h1.has_variances());
+        assert_eq!(h1.underflow().value, 0.0);
+        assert_eq!(h1.overflow().value, 2.0);
+        Ok(())
+    }
+
+    // Written by ROOT or uproot with fixtures/make_external_root_fixtures.py,
+    // which cannot run without either of them
+    #[test]
+    #[ignore = "needs fixtures/external_th1.root, see fixtures/make_external_root_fixtures.py"]
+    fn test_read_external() -> Result<(), Box<dyn Error>> {
+        let data = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/external_th1.root"
+        ))?;
+        let mut keys = list_keys(&data)?;
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                ("h_d".to_string(), "TH1D".to_string()),
+                ("h_f".to_string(), "TH1F".to_string())
+            ]
+        );
+
+        let h_d = read_root(&data, "h_d")?;
+        assert!(h_d.axis().as_any().is::<FixedWidthAxis>());
+        assert_eq!(*h_d.axis().bin_edges(), vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(h_d.bin_contents(), &vec![1.0, 3.0, 3.0, 1.0]);
+        assert_eq!(*h_d.variances(), vec![1.0, 5.0, 9.0, 1.0]);
+        assert_eq!(
+            h_d.underflow(),
+            Flow {
+                value: 1.0,
+                variance: 1.0
+            }
+        );
+        assert_eq!(
+            h_d.overflow(),
+            Flow {
+                value: 0.5,
+                variance: 0.25
+            }
+        );
+
+        let h_f = read_root(&data, "h_f")?;
+        assert!(h_f.axis().as_any().is::<GeneralAxis>());
+        assert_eq!(*h_f.axis().bin_edges(), vec![0.0, 1.0, 10.0, 100.0]);
+        assert_eq!(h_f.bin_contents(), &vec![2.0, 1.5, 1.0]);
+        assert_eq!(*h_f.variances(), vec![4.0, 1.25, 1.0]);
+        assert_eq!(
+            h_f.underflow(),
+            Flow {
+                value: 1.0,
+                variance: 1.0
+            }
+        );
+        assert_eq!(
+            h_f.overflow(),
+            Flow {
+                value: 1.5,
+                variance: 2.25
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_missing() {
+        assert!(read_root(TH1D_FIXED, "other").is_err());
+        assert!(read_root(b"not a root file", "h").is_err());
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), Box<dyn Error>> {
+        let mut h1 = H1::new(
+            Box::new(GeneralAxis::new(vec![-1.0, 0.0, 2.0])),
+            vec![0.0; 2],
+        );
+        h1.fill_many(&[-2.0, -0.5, 1.0, 1.5]);
+        h1.fill_weighted(3.0, 0.5);
+        h1.metadata_mut()
+            .insert("title".into(), "Some title".into());
+
+        let data = write_root(&h1, "test.root", "hist");
+        assert_eq!(
+            list_keys(&data)?,
+            vec![("hist".to_string(), "TH1D".to_string())]
+        );
+
+        let restored = read_root(&data, "hist")?;
+        assert_eq!(restored.axis().bin_edges(), h1.axis().bin_edges());
+        assert_eq!(restored.bin_contents(), h1.bin_contents());
+        assert_eq!(restored.variances(), h1.variances());
+        assert_eq!(restored.underflow(), h1.underflow());
+        assert_eq!(restored.overflow(), h1.overflow());
+        Ok(())
+    }
+
+    // Written by this module, compared byte for byte and read by uproot in tests/test_h1.py
+    const HISTR_WRITTEN: &[u8] = include_bytes!("../fixtures/histr_written.root");
+    const FIXED_DATIME: u32 = (2026 - 1995) << 26 | 1 << 22 | 1 << 17;
+
+    fn written_h1() -> H1<'static> {
+        let mut h1 = H1::new(
+            Box::new(FixedWidthAxis::new(0.0, 2.0, 0.5, 4)),
+            vec![0.0; 4],
+        );
+        h1.fill_many(&[-1.0, 0.2, 0.7, 0.8, 1.9, 2.5]);
+        h1.fill_weighted(1.2, 2.0);
+        h1.metadata_mut().insert("title".into(), "Energy".into());
+        h1
+    }
+
+    #[test]
+    fn test_written_fixture() -> Result<(), Box<dyn Error>> {
+        let data = write_root_at(&written_h1(), "histr_written.root", "hist", FIXED_DATIME, 1);
+        assert!(
+            data == HISTR_WRITTEN,
+            "fixtures/histr_written.root is out of date"
+        );
+
+        // As expected from uproot in tests/test_h1.py
+        let h1 = read_root(HISTR_WRITTEN, "hist")?;
+        assert_eq!(*h1.axis().bin_edges(), vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(h1.bin_contents(), &vec![1.0, 2.0, 2.0, 1.0]);
+        assert_eq!(*h1.variances(), vec![1.0, 2.0, 4.0, 1.0]);
+        assert_eq!(
+            h1.underflow(),
+            Flow {
+                value: 1.0,
+                variance: 1.0
+            }
+        );
+        assert_eq!(
+            h1.overflow(),
+            Flow {
+                value: 1.0,
+                variance: 1.0
+            }
+        );
+        Ok(())
+    }
+
+    // The file structure checked with oxyroot, a ROOT reader and writer that
+    // shares no code with this module.
+    #[test]
+    fn test_read_by_oxyroot() -> Result<(), Box<dyn Error>> {
+        let path = std::env::temp_dir().join(format!("histr-oxyroot-{}.root", std::process::id()));
+        for (data, name, class_name) in [
+            (HISTR_WRITTEN, "hist", "TH1D"),
+            (TH1D_FIXED, "h", "TH1D"),
+            (TH1F_VARIABLE_ZLIB, "energy", "TH1F"),
+        ] {
+            std::fs::write(&path, data)?;
+            let file = oxyroot::RootFile::open(&path);
+            std::fs::remove_file(&path)?;
+            let file = file?;
+            let names: Vec<&str> = file.keys_name().collect();
+            assert_eq!(names, vec![name]);
+            let keys = file.keys();
+            assert_eq!(keys[0].class_name(), class_name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_oxyroot_file() -> Result<(), Box<dyn Error>> {
+        let path = std::env::temp_dir().join(format!("histr-tree-{}.root", std::process::id()));
+        let mut file = oxyroot::RootFile::create(&path)?;
+        let mut tree = oxyroot::WriterTree::new("tree");
+        tree.new_branch("x", (0..10).map(|x| x as f64));
+        tree.write(&mut file)?;
+        file.close()?;
+        // Flushed on drop
+        drop(file);
+        let mut data = Vec::new();
+        File::open(&path)?.read_to_end(&mut data)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(
+            list_keys(&data)?,
+            vec![("tree".to_string(), "TTree".to_string())]
+        );
+        assert!(read_root(&data, "tree").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        // Errors for truncated and corrupted files, no panics
+        for data in [HISTR_WRITTEN, TH1D_FIXED, TH1F_VARIABLE_ZLIB] {
+            let name = &list_keys(data).unwrap()[0].0;
+            let h1 = read_root(data, name).unwrap();
+            for length in 0..data.len() {
+                // Truncating only the free segments at the end loses nothing
+                if let Ok(truncated) = read_root(&data[..length], name) {
+                    assert_eq!(truncated.bin_contents(), h1.bin_contents());
+                }
+            }
+            for position in 0..data.len() {
+                for value in [0x00, 0x7f, 0x80, 0xff] {
+                    let mut corrupted = data.to_vec();
+                    corrupted[position] = value;
+                    let _ = list_keys(&corrupted);
+                    let _ = read_root(&corrupted, name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_datime() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(20000), (2024, 10, 4));
+    }
+}
@@ -0,0 +1,495 @@
+//! Smoothing and interpolation of bin contents.
+//!
+//! Smoothing returns new histograms with the same axis; flow bins are copied
+//! unchanged. Interpolation is over the bin centers.
+
+use std::str::FromStr;
+
+use crate::h1::H1;
+use crate::special::erf;
+use crate::storage::Storage;
+
+/// Interpolation between the bin centers.
+///
+/// Outside of the first and last bin centers, the contents of the first and
+/// last bins are used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Content of the bin with the nearest center.
+    Nearest,
+    #[default]
+    Linear,
+    /// Natural cubic spline.
+    Cubic,
+}
+
+impl FromStr for Interpolation {
+    type Err = &'static str;
+
+    fn from_str(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "nearest" => Ok(Interpolation::Nearest),
+            "linear" => Ok(Interpolation::Linear),
+            "cubic" => Ok(Interpolation::Cubic),
+            _ => Err("Unknown interpolation."),
+        }
+    }
+}
+
+/// Interpolating function of the bin contents, built once for many points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interpolator {
+    mode: Interpolation,
+    centers: Vec<f64>,
+    values: Vec<f64>,
+    // Second derivatives of the cubic spline at the centers
+    second_derivatives: Vec<f64>,
+}
+
+impl Interpolator {
+    pub fn new<S: Storage>(h1: &H1<'_, S>, mode: Interpolation) -> Self {
+        let edges = h1.axis().bin_edges();
+        let centers: Vec<f64> = edges
+            .windows(2)
+            .map(|pair| (pair[0] + pair[1]) / 2.0)
+            .collect();
+        let values = h1.values().into_owned();
+        let second_derivatives = match mode {
+            Interpolation::Cubic => spline_second_derivatives(&centers, &values),
+            _ => Vec::new(),
+        };
+        Interpolator {
+            mode,
+            centers,
+            values,
+            second_derivatives,
+        }
+    }
+
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let n = self.centers.len();
+        if x.is_nan() {
+            return f64::NAN;
+        }
+        if x <= self.centers[0] {
+            return self.values[0];
+        }
+        if x >= self.centers[n - 1] {
+            return self.values[n - 1];
+        }
+        // Interval between the centers i and i + 1
+        let i = self.centers.partition_point(|center| *center <= x) - 1;
+        let (x0, x1) = (self.centers[i], self.centers[i + 1]);
+        let (y0, y1) = (self.values[i], self.values[i + 1]);
+        let t = (x - x0) / (x1 - x0);
+        match self.mode {
+            Interpolation::Nearest if t < 0.5 => y0,
+            Interpolation::Nearest => y1,
+            Interpolation::Linear => y0 + t * (y1 - y0),
+            Interpolation::Cubic => {
+                let h = x1 - x0;
+                let (d0, d1) = (self.second_derivatives[i], self.second_derivatives[i + 1]);
+                let s = 1.0 - t;
+                s * y0 + t * y1 + ((s * s * s - s) * d0 + (t * t * t - t) * d1) * h * h / 6.0
+            }
+        }
+    }
+}
+
+// Second derivatives of the natural cubic spline through the points (Thomas algorithm).
+fn spline_second_derivatives(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let mut second_derivatives = vec![0.0; n];
+    if n < 3 {
+        return second_derivatives;
+    }
+    // Forward elimination of the tridiagonal system for the inner points
+    let mut diagonal = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        let (h0, h1) = (x[i] - x[i - 1], x[i + 1] - x[i]);
+        let lower = if i > 1 { h0 } else { 0.0 };
+        diagonal[i] = 2.0 * (h0 + h1);
+        rhs[i] = 6.0 * ((y[i + 1] - y[i]) / h1 - (y[i] - y[i - 1]) / h0);
+        if i > 1 {
+            let factor = lower / diagonal[i - 1];
+            diagonal[i] -= factor * h0;
+            rhs[i] -= factor * rhs[i - 1];
+        }
+    }
+    for i in (1..n - 1).rev() {
+        let upper = if i < n - 2 { x[i + 1] - x[i] } else { 0.0 };
+        second_derivatives[i] = (rhs[i] - upper * second_derivatives[i + 1]) / diagonal[i];
+    }
+    second_derivatives
+}
+
+// Median of three values.
+fn median3(a: f64, b: f64, c: f64) -> f64 {
+    a.max(b).min(a.min(b).max(c))
+}
+
+fn median5(values: &[f64]) -> f64 {
+    let mut sorted = [values[0], values[1], values[2], values[3], values[4]];
+    sorted.sort_by(f64::total_cmp);
+    sorted[2]
+}
+
+// One pass of 353QH with hanning on the values, without the residual step.
+fn smooth_353qh_once(values: &[f64]) -> Vec<f64> {
+    let n = values.len();
+    let mut z = values.to_vec();
+
+    // Running medians of 3, 5 and 3
+    for kk in 0..3 {
+        let y = z.clone();
+        if kk == 1 {
+            for i in 2..n - 2 {
+                z[i] = median5(&y[i - 2..=i + 2]);
+            }
+            z[1] = median3(y[0], y[1], y[2]);
+            z[n - 2] = median3(y[n - 3], y[n - 2], y[n - 1]);
+        } else {
+            for i in 1..n - 1 {
+                z[i] = median3(y[i - 1], y[i], y[i + 1]);
+            }
+            if kk == 0 {
+                z[0] = median3(z[1], z[0], 3.0 * z[1] - 2.0 * z[2]);
+                z[n - 1] = median3(z[n - 2], z[n - 1], 3.0 * z[n - 2] - 2.0 * z[n - 3]);
+            }
+        }
+    }
+
+    // Quadratic interpolation of flat segments of three points
+    let mut y = z.clone();
+    for i in 2..n.saturating_sub(2) {
+        if z[i - 1] != z[i] || z[i] != z[i + 1] {
+            continue;
+        }
+        let (left, right) = (z[i - 2] - z[i], z[i + 2] - z[i]);
+        if left * right <= 0.0 {
+            continue;
+        }
+        // Coefficients as in ROOT, towards the side with the smaller step
+        let (near, back, far) = if right.abs() > left.abs() {
+            (i - 1, i + 2, i - 2)
+        } else {
+            (i + 1, i - 2, i + 2)
+        };
+        y[i] = -0.5 * z[back] + z[i] / 0.75 + z[far] / 6.0;
+        y[near] = 0.5 * (z[far] - z[back]) + z[i];
+    }
+
+    // Hanning (running means with weights 1/4, 1/2, 1/4)
+    let mut smoothed = y.clone();
+    for i in 1..n - 1 {
+        smoothed[i] = 0.25 * y[i - 1] + 0.5 * y[i] + 0.25 * y[i + 1];
+    }
+    smoothed
+}
+
+// Normal distribution function.
+fn phi(x: f64) -> f64 {
+    (1.0 + erf(x / 2f64.sqrt())) / 2.0
+}
+
+impl<S: Storage> H1<'_, S> {
+    /// Interpolated content at `x`, see `Interpolator` for many points.
+    pub fn interpolate(&self, x: f64, mode: Interpolation) -> f64 {
+        Interpolator::new(self, mode).evaluate(x)
+    }
+
+    pub fn interpolator(&self, mode: Interpolation) -> Interpolator {
+        Interpolator::new(self, mode)
+    }
+
+    // New histogram with the same axis and flows.
+    fn with_contents(&self, values: Vec<f64>, variances: Vec<f64>) -> H1<'static> {
+        let mut h1 = H1::new(self.axis().clone_box(), values);
+        h1.set_variances(Some(variances))
+            .expect("lengths are equal");
+        h1.set_underflow(self.underflow());
+        h1.set_overflow(self.overflow());
+        h1
+    }
+
+    /// Mean of the contents in a centered window of an odd number of bins.
+    ///
+    /// The window is shortened at the ends of the axis. Variances are those
+    /// of the means, without the correlations between neighbouring bins.
+    pub fn smooth_moving_average(&self, window: usize) -> Result<H1<'static>, &'static str> {
+        if window.is_multiple_of(2) {
+            return Err("Window must be an odd number of bins.");
+        }
+        let (values, variances) = (self.values(), self.variances());
+        let n = values.len();
+        let half = window / 2;
+        let (mut smoothed, mut smoothed_variances) = (Vec::with_capacity(n), Vec::with_capacity(n));
+        for i in 0..n {
+            let range = i.saturating_sub(half)..(i + half + 1).min(n);
+            let count = range.len() as f64;
+            smoothed.push(values[range.clone()].iter().sum::<f64>() / count);
+            smoothed_variances.push(variances[range].iter().sum::<f64>() / (count * count));
+        }
+        Ok(self.with_contents(smoothed, smoothed_variances))
+    }
+
+    /// Convolution with a Gaussian of standard deviation `sigma` (in units of x).
+    ///
+    /// The contents are treated as densities constant within the bins, so
+    /// bins of different widths are weighted by the Gaussian integrated over
+    /// them. The Gaussian is truncated at the ends of the axis and
+    /// renormalized, so that flat densities stay flat.
+    pub fn smooth_gaussian(&self, sigma: f64) -> Result<H1<'static>, &'static str> {
+        if !(sigma.is_finite() && sigma > 0.0) {
+            return Err("Sigma must be positive.");
+        }
+        let edges = self.axis().bin_edges();
+        let widths: Vec<f64> = edges.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        let (values, variances) = (self.values(), self.variances());
+        let n = values.len();
+        let (mut smoothed, mut smoothed_variances) = (Vec::with_capacity(n), Vec::with_capacity(n));
+        for i in 0..n {
+            let center = (edges[i] + edges[i + 1]) / 2.0;
+            let cdf: Vec<f64> = edges
+                .iter()
+                .map(|edge| phi((edge - center) / sigma))
+                .collect();
+            let weights: Vec<f64> = cdf.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            let total: f64 = weights.iter().sum();
+            let (mut density, mut variance) = (0.0, 0.0);
+            for j in 0..n {
+                let weight = weights[j] / total / widths[j];
+                density += weight * values[j];
+                variance += weight * weight * variances[j];
+            }
+            smoothed.push(density * widths[i]);
+            smoothed_variances.push(variance * widths[i] * widths[i]);
+        }
+        Ok(self.with_contents(smoothed, smoothed_variances))
+    }
+
+    /// Smoothing with the 353QH algorithm twice (Friedman), as ROOT's `TH1::Smooth`.
+    ///
+    /// Each of the `times` passes applies running medians of 3, 5 and 3,
+    /// quadratic interpolation of flat segments and hanning, to the contents
+    /// and then to the residuals, which are added back. Contents stay non
+    /// negative unless some were negative before the pass. As in ROOT, the
+    /// variances are kept.
+    pub fn smooth_353qh(&self, times: usize) -> Result<H1<'static>, &'static str> {
+        let mut values = self.values().into_owned();
+        if values.len() < 3 {
+            return Err("At least three bins are required.");
+        }
+        for _ in 0..times {
+            let has_negative = values.iter().any(|value| *value < 0.0);
+            let smoothed = smooth_353qh_once(&values);
+            let residuals: Vec<f64> = values.iter().zip(&smoothed).map(|(v, s)| v - s).collect();
+            let smoothed_residuals = smooth_353qh_once(&residuals);
+            for ((value, s), r) in values.iter_mut().zip(&smoothed).zip(&smoothed_residuals) {
+                *value = if has_negative {
+                    s + r
+                } else {
+                    (s + r).max(0.0)
+                };
+            }
+        }
+        Ok(self.with_contents(values, self.variances().into_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::{FixedWidthAxis, GeneralAxis};
+    use crate::h1::Flow;
+
+    // Bin centers at 0.5, 1.5, 3 and 6
+    fn h1(values: &[f64]) -> H1<'static> {
+        H1::new(
+            Box::new(GeneralAxis::new(vec![0.0, 1.0, 2.0, 4.0, 8.0])),
+            values.to_vec(),
+        )
+    }
+
+    fn fixed(values: &[f64]) -> H1<'static> {
+        let n = values.len();
+        H1::new(
+            Box::new(FixedWidthAxis::new(0.0, n as f64, 1.0, n)),
+            values.to_vec(),
+        )
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-12 * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let source = h1(&[1.0, 4.0, 2.0, 5.0]);
+        assert_eq!(source.interpolate(1.0, Interpolation::Nearest), 4.0);
+        assert_eq!(source.interpolate(0.9, Interpolation::Nearest), 1.0);
+        assert_close(source.interpolate(1.0, Interpolation::Linear), 2.5);
+        assert_close(source.interpolate(4.5, Interpolation::Linear), 3.5);
+        // Reference values from mpmath
+        let spline = source.interpolator(Interpolation::Cubic);
+        assert_close(spline.evaluate(1.0), 2.872_807_017_543_859_6);
+        assert_close(spline.evaluate(2.0), 3.878_492_527_615_334_6);
+        assert_close(spline.evaluate(4.5), 2.065_789_473_684_210_5);
+
+        // Through the points, flat outside of the centers
+        for mode in [
+            Interpolation::Nearest,
+            Interpolation::Linear,
+            Interpolation::Cubic,
+        ] {
+            assert_close(source.interpolate(3.0, mode), 2.0);
+            assert_eq!(source.interpolate(-1.0, mode), 1.0);
+            assert_eq!(source.interpolate(10.0, mode), 5.0);
+            assert!(source.interpolate(f64::NAN, mode).is_nan());
+        }
+        assert_eq!("cubic".parse::<Interpolation>(), Ok(Interpolation::Cubic));
+        assert!("quadratic".parse::<Interpolation>().is_err());
+    }
+
+    #[test]
+    fn test_smooth_moving_average() {
+        let mut source = fixed(&[3.0, 0.0, 6.0, 3.0, 9.0]);
+        source.set_overflow(Flow {
+            value: 2.0,
+            variance: 2.0,
+        });
+        let smoothed = source.smooth_moving_average(3).unwrap();
+        assert_eq!(smoothed.values().as_ref(), &vec![1.5, 3.0, 3.0, 6.0, 6.0]);
+        assert_eq!(
+            smoothed.variances().as_ref(),
+            &vec![0.75, 1.0, 1.0, 2.0, 3.0]
+        );
+        assert_eq!(smoothed.overflow(), source.overflow());
+        assert_eq!(
+            source.smooth_moving_average(1).unwrap().values(),
+            source.values()
+        );
+        assert!(source.smooth_moving_average(2).is_err());
+    }
+
+    #[test]
+    fn test_smooth_gaussian() {
+        // Reference values from mpmath
+        let smoothed = h1(&[1.0, 4.0, 2.0, 5.0]).smooth_gaussian(1.0).unwrap();
+        let expected = [
+            2.048_862_679_117_686_4,
+            2.232_679_019_361_36,
+            2.895_967_937_725_767,
+            4.977_105_634_257_443,
+        ];
+        for (value, expected) in smoothed.values().iter().zip(expected) {
+            assert_close(*value, expected);
+        }
+
+        // Flat densities stay flat, also with bins of different widths
+        let flat = h1(&[1.0, 1.0, 2.0, 4.0]).smooth_gaussian(2.0).unwrap();
+        for (value, expected) in flat.values().iter().zip([1.0, 1.0, 2.0, 4.0]) {
+            assert_close(*value, expected);
+        }
+        assert!(flat.variances().iter().all(|variance| *variance > 0.0));
+        assert!(flat.variances()[3] < 4.0);
+        assert!(h1(&[1.0; 4]).smooth_gaussian(0.0).is_err());
+    }
+
+    #[test]
+    fn test_smooth_353qh() {
+        // Lines and constants are kept
+        let line: Vec<f64> = (0..10).map(|i| 2.0 + 0.5 * i as f64).collect();
+        let smoothed = fixed(&line).smooth_353qh(1).unwrap();
+        for (value, expected) in smoothed.values().iter().zip(&line) {
+            assert_close(*value, *expected);
+        }
+        assert_eq!(
+            fixed(&[3.0; 6]).smooth_353qh(2).unwrap().values().as_ref(),
+            &vec![3.0; 6]
+        );
+
+        // Single spikes are removed, contents stay non negative
+        let mut values = vec![5.0; 12];
+        values[4] = 50.0;
+        values[8] = 0.0;
+        let source = fixed(&values);
+        let smoothed = source.smooth_353qh(1).unwrap();
+        assert!(
+            smoothed
+                .values()
+                .iter()
+                .all(|value| (*value - 5.0).abs() < 1e-12),
+            "{:?}",
+            smoothed.values()
+        );
+        assert_eq!(smoothed.variances(), source.variances());
+        assert_eq!(source.smooth_353qh(0).unwrap().values(), source.values());
+        assert!(fixed(&[1.0, 2.0]).smooth_353qh(1).is_err());
+    }
+
+    #[test]
+    fn test_smooth_353qh_reference() {
+        // Reference values from a line-by-line Python transcription of ROOT's
+        // TH1::SmoothArray, the flat segment takes both sides of the quadratic
+        // interpolation
+        let source = fixed(&[0.0, 1.0, 4.0, 7.0, 7.0, 7.0, 3.0, 1.0, 0.0, 2.0, 5.0, 4.0]);
+        let once = [
+            0.0,
+            1.375,
+            4.0,
+            6.75,
+            7.625,
+            5.875,
+            3.0,
+            1.1666666666666665,
+            0.9166666666666666,
+            1.9895833333333333,
+            3.4270833333333335,
+            4.0,
+        ];
+        let twice = [
+            0.0,
+            1.703125,
+            4.137532552083333,
+            6.49072265625,
+            7.24072265625,
+            5.753092447916667,
+            3.114239728009259,
+            1.306007667824074,
+            1.0328595196759258,
+            1.9148763020833333,
+            3.155490451388889,
+            4.0,
+        ];
+        for (times, expected) in [(1, &once), (2, &twice)] {
+            let smoothed = source.smooth_353qh(times).unwrap();
+            for (value, expected) in smoothed.values().iter().zip(expected) {
+                assert_close(*value, *expected);
+            }
+        }
+
+        // Negative contents are only kept in passes that start with some
+        let smoothed = fixed(&[9.0, 2.0, 9.0, -1.0, 9.0, 0.0, 0.0, 1.0, 9.0])
+            .smooth_353qh(2)
+            .unwrap();
+        let expected = [
+            9.403645833333334,
+            9.0,
+            7.166106047453704,
+            4.023491753472223,
+            1.2977430555555556,
+            0.0,
+            0.109375,
+            1.3859953703703705,
+            3.1809172453703707,
+        ];
+        for (value, expected) in smoothed.values().iter().zip(&expected) {
+            assert_close(*value, *expected);
+        }
+    }
+}
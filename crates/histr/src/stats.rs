@@ -0,0 +1,630 @@
+//! Statistics of histograms and tests for the compatibility of two histograms.
+//!
+//! Moments are computed from the bin centers. Tests require histograms with
+//! equal bins, flow bins are not included. One-sample tests compare a
+//! histogram with a distribution given by its cumulative distribution
+//! function, restricted to the range of the axis.
+
+use crate::axis::Axis;
+use crate::h1::H1;
+use crate::special::{anderson_darling_k_sample_sf, anderson_darling_sf, chi2_sf, kolmogorov_sf};
+use crate::storage::Storage;
+
+/// Moments of the distribution of the bin contents, using the bin centers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moments {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub skewness: f64,
+    /// Excess kurtosis (0 for a normal distribution).
+    pub kurtosis: f64,
+}
+
+/// Result of a chi-square test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chi2Result {
+    pub statistic: f64,
+    pub ndf: usize,
+    pub p_value: f64,
+}
+
+/// Result of a test with a statistic other than chi-square.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+/// Moments of the histogram, NaN if the histogram has no entries.
+pub fn moments<S: Storage>(h1: &H1<'_, S>) -> Moments {
+    let edges = h1.axis().bin_edges();
+    let values = h1.values();
+    let centers: Vec<f64> = edges
+        .windows(2)
+        .map(|pair| (pair[0] + pair[1]) / 2.0)
+        .collect();
+    let total: f64 = values.iter().sum();
+    let mean = values.iter().zip(&centers).map(|(w, x)| w * x).sum::<f64>() / total;
+    let central_moment = |k: i32| {
+        values
+            .iter()
+            .zip(&centers)
+            .map(|(w, x)| w * (x - mean).powi(k))
+            .sum::<f64>()
+            / total
+    };
+    let variance = central_moment(2);
+    Moments {
+        mean,
+        std_dev: variance.sqrt(),
+        skewness: central_moment(3) / variance.powf(1.5),
+        kurtosis: central_moment(4) / (variance * variance) - 3.0,
+    }
+}
+
+pub(crate) fn check_axes(first: &dyn Axis, second: &dyn Axis) -> Result<(), &'static str> {
+    if first.equal_bins(second) {
+        Ok(())
+    } else {
+        Err("Histograms must have equal bins.")
+    }
+}
+
+// Whether the variances differ from the values (i.e. not Poisson counts).
+fn is_weighted<S: Storage>(h1: &H1<'_, S>) -> bool {
+    h1.values() != h1.variances()
+}
+
+/// Pearson chi-square test for the homogeneity of two histograms.
+///
+/// The test follows Gagunashvili, with the totals as free parameters.
+/// Histograms whose variances equal their values are treated as unweighted
+/// (counts), others as weighted, and the statistic for unweighted/unweighted,
+/// unweighted/weighted or weighted/weighted histograms is used accordingly.
+/// Bins empty in both histograms are not counted in the degrees of freedom.
+///
+/// In the unweighted/weighted test, an empty bin of the weighted histogram
+/// gets the variance of one entry with the mean weight.
+pub fn chi2_test<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<Chi2Result, &'static str> {
+    check_axes(first.axis(), second.axis())?;
+    let first_bins = Bins::new(first)?;
+    let second_bins = Bins::new(second)?;
+    let (statistic, bins) = match (is_weighted(first), is_weighted(second)) {
+        (false, false) => chi2_unweighted(&first_bins, &second_bins),
+        (false, true) => chi2_unweighted_weighted(&first_bins, &second_bins),
+        (true, false) => chi2_unweighted_weighted(&second_bins, &first_bins),
+        (true, true) => chi2_weighted(&first_bins, &second_bins),
+    };
+    chi2_result(statistic, bins)
+}
+
+/// Likelihood-ratio (G) test for the homogeneity of two unweighted histograms.
+///
+/// The statistic is asymptotically chi-square distributed, like Pearson's.
+pub fn g_test<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<Chi2Result, &'static str> {
+    check_axes(first.axis(), second.axis())?;
+    if is_weighted(first) || is_weighted(second) {
+        return Err("Weighted histograms are not supported.");
+    }
+    let (n, m) = (Bins::new(first)?, Bins::new(second)?);
+    let total = n.total + m.total;
+    // Observed times the log of observed over expected, 0 for empty bins
+    let term = |observed: f64, expected: f64| {
+        if observed > 0.0 {
+            observed * (observed / expected).ln()
+        } else {
+            0.0
+        }
+    };
+    let mut statistic = 0.0;
+    let mut bins = 0;
+    for (n_i, m_i) in n.values.iter().zip(&m.values) {
+        let sum = n_i + m_i;
+        if sum > 0.0 {
+            statistic += term(*n_i, n.total * sum / total) + term(*m_i, m.total * sum / total);
+            bins += 1;
+        }
+    }
+    chi2_result(2.0 * statistic, bins)
+}
+
+// Values and variances of a histogram used in the tests.
+struct Bins {
+    values: Vec<f64>,
+    variances: Vec<f64>,
+    total: f64,
+}
+
+impl Bins {
+    fn new<S: Storage>(h1: &H1<'_, S>) -> Result<Self, &'static str> {
+        let values = h1.values().into_owned();
+        if values.iter().any(|value| *value < 0.0) {
+            return Err("Bin contents must not be negative.");
+        }
+        let total: f64 = values.iter().sum();
+        if total == 0.0 {
+            return Err("Histograms must not be empty.");
+        }
+        Ok(Bins {
+            values,
+            variances: h1.variances().into_owned(),
+            total,
+        })
+    }
+
+    // Number of entries with unit weight giving the same relative error of the total.
+    fn effective_entries(&self) -> f64 {
+        self.total * self.total / self.variances.iter().sum::<f64>()
+    }
+
+    // Normalized cumulative bin contents.
+    fn cumulative(&self) -> Vec<f64> {
+        self.values
+            .iter()
+            .scan(0.0, |sum, value| {
+                *sum += value;
+                Some(*sum / self.total)
+            })
+            .collect()
+    }
+}
+
+fn chi2_result(statistic: f64, bins: usize) -> Result<Chi2Result, &'static str> {
+    if bins < 2 {
+        return Err("At least two non-empty bins are required.");
+    }
+    let ndf = bins - 1;
+    Ok(Chi2Result {
+        statistic,
+        ndf,
+        p_value: chi2_sf(statistic, ndf as f64),
+    })
+}
+
+// Statistic and number of non-empty bins.
+fn chi2_unweighted(n: &Bins, m: &Bins) -> (f64, usize) {
+    let mut statistic = 0.0;
+    let mut bins = 0;
+    for (n_i, m_i) in n.values.iter().zip(&m.values) {
+        if n_i + m_i > 0.0 {
+            statistic += (m.total * n_i - n.total * m_i).powi(2) / (n_i + m_i);
+            bins += 1;
+        }
+    }
+    (statistic / (n.total * m.total), bins)
+}
+
+fn chi2_unweighted_weighted(n: &Bins, w: &Bins) -> (f64, usize) {
+    let mean_weight = w.variances.iter().sum::<f64>() / w.total;
+    let mut statistic = 0.0;
+    let mut bins = 0;
+    for ((n_i, w_i), s2_i) in n.values.iter().zip(&w.values).zip(&w.variances) {
+        if *n_i == 0.0 && *w_i == 0.0 && *s2_i == 0.0 {
+            continue;
+        }
+        let s2_i = if *s2_i > 0.0 {
+            *s2_i
+        } else {
+            mean_weight * mean_weight
+        };
+        // Estimate of the probability of the bin
+        let d = w.total * w_i - n.total * s2_i;
+        let p =
+            (d + (d * d + 4.0 * w.total * w.total * s2_i * n_i).sqrt()) / (2.0 * w.total * w.total);
+        statistic +=
+            (n_i - n.total * p).powi(2) / (n.total * p) + (w_i - w.total * p).powi(2) / s2_i;
+        bins += 1;
+    }
+    (statistic, bins)
+}
+
+fn chi2_weighted(first: &Bins, second: &Bins) -> (f64, usize) {
+    let mut statistic = 0.0;
+    let mut bins = 0;
+    for i in 0..first.values.len() {
+        let denominator =
+            first.total.powi(2) * second.variances[i] + second.total.powi(2) * first.variances[i];
+        if denominator > 0.0 {
+            statistic += (first.total * second.values[i] - second.total * first.values[i]).powi(2)
+                / denominator;
+            bins += 1;
+        }
+    }
+    (statistic, bins)
+}
+
+/// Two-sample Kolmogorov–Smirnov test on the cumulative bin contents.
+///
+/// The statistic is the largest distance of the normalized cumulative
+/// distributions. The p-value uses the effective numbers of entries and the
+/// asymptotic Kolmogorov distribution; for binned data it is conservative.
+pub fn ks_test<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<TestResult, &'static str> {
+    check_axes(first.axis(), second.axis())?;
+    let (first, second) = (Bins::new(first)?, Bins::new(second)?);
+    let statistic = first
+        .cumulative()
+        .iter()
+        .zip(&second.cumulative())
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f64::max);
+
+    let (n, m) = (first.effective_entries(), second.effective_entries());
+    let effective_n = (n * m / (n + m)).sqrt();
+    let lambda = (effective_n + 0.12 + 0.11 / effective_n) * statistic;
+    Ok(TestResult {
+        statistic,
+        p_value: kolmogorov_sf(lambda),
+    })
+}
+
+/// Two-sample Anderson–Darling test on the cumulative bin contents.
+///
+/// The statistic is the k-sample statistic of Scholz & Stephens for samples
+/// with ties (midranks), with the entries of each bin as tied values. Weighted
+/// histograms are scaled to their effective numbers of entries. The p-value
+/// is interpolated from the tabulated critical values of the standardized
+/// statistic and clamped to [0.001, 0.25].
+pub fn ad_test<S: Storage, T: Storage>(
+    first: &H1<'_, S>,
+    second: &H1<'_, T>,
+) -> Result<TestResult, &'static str> {
+    check_axes(first.axis(), second.axis())?;
+    let samples = [Bins::new(first)?, Bins::new(second)?];
+    let counts: Vec<Vec<f64>> = samples
+        .iter()
+        .map(|bins| {
+            let scale = bins.effective_entries() / bins.total;
+            bins.values.iter().map(|value| value * scale).collect()
+        })
+        .collect();
+    let sizes: Vec<f64> = counts.iter().map(|counts| counts.iter().sum()).collect();
+    let n: f64 = sizes.iter().sum();
+    if n < 4.0 {
+        return Err("At least four entries are required.");
+    }
+
+    let mut statistic = 0.0;
+    let mut cumulative = [0.0; 2];
+    let mut pooled_cumulative = 0.0;
+    for (a, b) in counts[0].iter().zip(&counts[1]) {
+        let bin_counts = [*a, *b];
+        let tied = a + b;
+        if tied == 0.0 {
+            continue;
+        }
+        pooled_cumulative += tied;
+        let pooled_midrank = pooled_cumulative - tied / 2.0;
+        let denominator = pooled_midrank * (n - pooled_midrank) - n * tied / 4.0;
+        if denominator <= 0.0 {
+            continue;
+        }
+        for i in 0..2 {
+            cumulative[i] += bin_counts[i];
+            let midrank = cumulative[i] - bin_counts[i] / 2.0;
+            statistic +=
+                tied * (n * midrank - sizes[i] * pooled_midrank).powi(2) / (sizes[i] * denominator);
+        }
+    }
+    statistic *= (n - 1.0) / (n * n);
+
+    let standardized = (statistic - 1.0) / ad_k_sample_variance(&sizes).sqrt();
+    Ok(TestResult {
+        statistic,
+        p_value: anderson_darling_k_sample_sf(standardized, sizes.len()),
+    })
+}
+
+// Variance of the k-sample Anderson–Darling statistic (Scholz & Stephens, eq. 4).
+fn ad_k_sample_variance(sizes: &[f64]) -> f64 {
+    let total: f64 = sizes.iter().sum();
+    let n = total.round() as usize;
+    let k = sizes.len() as f64;
+    let big_h: f64 = sizes.iter().map(|size| 1.0 / size).sum();
+    // h = sum of 1/i for i < n, g = sum of 1/((n - i) j) for i < j < n
+    let (h, g) = if n <= 1_000_000 {
+        let mut h = 0.0;
+        let mut g = 0.0;
+        for i in (1..n).rev() {
+            g += h / (n - i) as f64;
+            h += 1.0 / i as f64;
+        }
+        (h, g)
+    } else {
+        let m = (n - 1) as f64;
+        (
+            m.ln() + 0.577_215_664_901_532_9 + 0.5 / m,
+            std::f64::consts::PI.powi(2) / 6.0,
+        )
+    };
+    let a = (4.0 * g - 6.0) * (k - 1.0) + (10.0 - 6.0 * g) * big_h;
+    let b = (2.0 * g - 4.0) * k * k + 8.0 * h * k + (2.0 * g - 14.0 * h - 4.0) * big_h - 8.0 * h
+        + 4.0 * g
+        - 6.0;
+    let c = (6.0 * h + 2.0 * g - 2.0) * k * k
+        + (4.0 * h - 4.0 * g + 6.0) * k
+        + (2.0 * h - 6.0) * big_h
+        + 4.0 * h;
+    let d = (2.0 * h + 6.0) * k * k - 4.0 * h * k;
+    (a * total.powi(3) + b * total.powi(2) + c * total + d)
+        / ((total - 1.0) * (total - 2.0) * (total - 3.0))
+}
+
+// Probabilities of the bins under a distribution, normalized to the axis range.
+fn bin_probabilities(axis: &dyn Axis, cdf: impl Fn(f64) -> f64) -> Result<Vec<f64>, &'static str> {
+    let cdf_values: Vec<f64> = axis.bin_edges().iter().map(|edge| cdf(*edge)).collect();
+    let total = cdf_values[cdf_values.len() - 1] - cdf_values[0];
+    if total.is_nan() || total <= 0.0 {
+        return Err("The distribution must have probability in the axis range.");
+    }
+    let probabilities: Vec<f64> = cdf_values
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]) / total)
+        .collect();
+    if probabilities.iter().any(|p| p.is_nan() || *p < 0.0) {
+        return Err("The cumulative distribution function must be non-decreasing.");
+    }
+    Ok(probabilities)
+}
+
+// Cumulative sums of the bin probabilities.
+fn cumulative_probabilities(probabilities: &[f64]) -> Vec<f64> {
+    probabilities
+        .iter()
+        .scan(0.0, |sum, p| {
+            *sum += p;
+            Some(*sum)
+        })
+        .collect()
+}
+
+/// One-sample Kolmogorov–Smirnov test against a cumulative distribution function.
+///
+/// The distribution is restricted to the range of the axis and compared with
+/// the normalized cumulative bin contents at the bin edges. The p-value uses
+/// the effective number of entries, as in the two-sample test.
+pub fn ks_test_cdf<S: Storage>(
+    h1: &H1<'_, S>,
+    cdf: impl Fn(f64) -> f64,
+) -> Result<TestResult, &'static str> {
+    let expected = cumulative_probabilities(&bin_probabilities(h1.axis(), cdf)?);
+    let bins = Bins::new(h1)?;
+    let statistic = bins
+        .cumulative()
+        .iter()
+        .zip(&expected)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f64::max);
+
+    let n = bins.effective_entries().sqrt();
+    let lambda = (n + 0.12 + 0.11 / n) * statistic;
+    Ok(TestResult {
+        statistic,
+        p_value: kolmogorov_sf(lambda),
+    })
+}
+
+/// One-sample Anderson–Darling test against a cumulative distribution function.
+///
+/// The statistic is the discrete version of Choulakian, Lockhart & Stephens,
+/// with the distribution restricted to the range of the axis. The p-value is
+/// from the asymptotic distribution for continuous data, which is
+/// conservative for coarse bins.
+pub fn ad_test_cdf<S: Storage>(
+    h1: &H1<'_, S>,
+    cdf: impl Fn(f64) -> f64,
+) -> Result<TestResult, &'static str> {
+    let probabilities = bin_probabilities(h1.axis(), cdf)?;
+    let expected = cumulative_probabilities(&probabilities);
+    let bins = Bins::new(h1)?;
+    let sum: f64 = bins
+        .cumulative()
+        .iter()
+        .zip(&expected)
+        .zip(&probabilities)
+        .filter(|((_, h), _)| **h > 0.0 && **h < 1.0)
+        .map(|((s, h), p)| (s - h).powi(2) * p / (h * (1.0 - h)))
+        .sum();
+    let statistic = bins.effective_entries() * sum;
+    Ok(TestResult {
+        statistic,
+        p_value: anderson_darling_sf(statistic),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::FixedWidthAxis;
+
+    fn h1(values: &[f64]) -> H1<'static> {
+        let n = values.len();
+        H1::new(
+            Box::new(FixedWidthAxis::new(0.0, n as f64, 1.0, n)),
+            values.to_vec(),
+        )
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9 * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_moments() {
+        let moments = moments(&h1(&[1.0, 2.0, 1.0]));
+        assert_eq!(moments.mean, 1.5);
+        assert_close(moments.std_dev, 0.5f64.sqrt());
+        assert_eq!(moments.skewness, 0.0);
+        assert_close(moments.kurtosis, -1.0);
+
+        assert!(super::moments(&h1(&[0.0, 0.0])).mean.is_nan());
+    }
+
+    #[test]
+    fn test_chi2_test() {
+        // Same as the chi-square test of the 2x3 contingency table
+        let result = chi2_test(&h1(&[10.0, 20.0, 30.0]), &h1(&[15.0, 15.0, 30.0])).unwrap();
+        assert_close(result.statistic, 12.0 / 7.0);
+        assert_eq!(result.ndf, 2);
+        assert_close(result.p_value, 0.424_372_845_676_949_95);
+
+        let same = chi2_test(&h1(&[1.0, 0.0, 3.0]), &h1(&[2.0, 0.0, 6.0])).unwrap();
+        assert_eq!(same.statistic, 0.0);
+        assert_eq!(same.ndf, 1);
+        assert_eq!(same.p_value, 1.0);
+
+        assert!(chi2_test(&h1(&[1.0, 2.0]), &h1(&[1.0, 2.0, 3.0])).is_err());
+        assert!(chi2_test(&h1(&[1.0, 0.0]), &h1(&[2.0, 0.0])).is_err());
+    }
+
+    fn weighted_h1(values: &[f64], variances: &[f64]) -> H1<'static> {
+        let mut h1 = h1(values);
+        h1.set_variances(Some(variances.to_vec())).unwrap();
+        h1
+    }
+
+    // Reference values below are from an independent implementation in Python
+    // (with mpmath for the p-values).
+
+    #[test]
+    fn test_chi2_test_weighted() {
+        let n = h1(&[10.0, 20.0, 30.0, 5.0]);
+        let w = weighted_h1(&[12.5, 18.0, 27.0, 4.0], &[6.25, 9.5, 14.0, 3.0]);
+        let result = chi2_test(&n, &w).unwrap();
+        assert_close(result.statistic, 0.697_514_973_732_615_8);
+        assert_eq!(result.ndf, 3);
+        assert_close(result.p_value, 0.873_788_296_196_170_2);
+        assert_eq!(chi2_test(&w, &n).unwrap(), result);
+        // Independent of the normalization of the weighted histogram
+        assert_close(
+            chi2_test(&n, &(&w * 3.0).unwrap()).unwrap().statistic,
+            result.statistic,
+        );
+
+        let v = weighted_h1(&[8.0, 22.5, 31.0, 6.0], &[5.0, 12.0, 20.0, 4.5]);
+        let result = chi2_test(&v, &w).unwrap();
+        assert_close(result.statistic, 3.302_086_872_503_993_5);
+        assert_eq!(result.ndf, 3);
+        assert_close(result.p_value, 0.347_352_296_535_091_6);
+        assert_close(
+            chi2_test(&(&v * 0.1).unwrap(), &w).unwrap().statistic,
+            result.statistic,
+        );
+
+        let empty_bin = weighted_h1(&[12.5, 0.0, 27.0, 4.0], &[6.25, 0.0, 14.0, 3.0]);
+        let result = chi2_test(&n, &empty_bin).unwrap();
+        assert!(result.statistic.is_finite() && result.p_value < 1e-3);
+    }
+
+    #[test]
+    fn test_g_test() {
+        let result = g_test(&h1(&[10.0, 20.0, 30.0, 5.0]), &h1(&[15.0, 15.0, 30.0, 0.0])).unwrap();
+        assert_close(result.statistic, 8.454_929_425_801_1);
+        assert_eq!(result.ndf, 3);
+        assert_close(result.p_value, 0.037_488_354_650_670_83);
+
+        let weighted = weighted_h1(&[1.0, 2.0], &[2.0, 2.0]);
+        assert!(g_test(&h1(&[1.0, 2.0]), &weighted).is_err());
+    }
+
+    #[test]
+    fn test_ks_test() {
+        let result = ks_test(&h1(&[50.0, 30.0, 20.0]), &h1(&[30.0, 30.0, 40.0])).unwrap();
+        assert_close(result.statistic, 0.2);
+        // Effective n = 50, p-value from mpmath
+        assert_close(result.p_value, 0.031_376_652_153_072_55);
+
+        let same = ks_test(&h1(&[1.0, 2.0]), &h1(&[2.0, 4.0])).unwrap();
+        assert_eq!(same.statistic, 0.0);
+        assert_eq!(same.p_value, 1.0);
+    }
+
+    #[test]
+    fn test_ad_test() {
+        let result = ad_test(
+            &h1(&[12.0, 25.0, 40.0, 18.0, 5.0]),
+            &h1(&[20.0, 30.0, 30.0, 10.0, 2.0]),
+        )
+        .unwrap();
+        assert_close(result.statistic, 5.113_795_199_214_850_4);
+        assert_close(result.p_value, 0.002_387_580_469_810_585_8);
+
+        let result = ad_test(&h1(&[50.0, 30.0, 20.0]), &h1(&[30.0, 30.0, 40.0])).unwrap();
+        assert_close(result.statistic, 9.620_393_374_741_2);
+        assert_eq!(result.p_value, 0.001);
+
+        let same = ad_test(&h1(&[10.0, 20.0, 10.0]), &h1(&[10.0, 20.0, 10.0])).unwrap();
+        assert_eq!(same.p_value, 0.25);
+        assert!(ad_test(&h1(&[1.0, 0.0]), &h1(&[1.0, 0.0])).is_err());
+    }
+
+    // Standard normal distribution by Simpson's rule, the standard library has no erf.
+    fn normal_cdf(x: f64) -> f64 {
+        let steps = 10_000;
+        let step = (x + 10.0) / steps as f64;
+        let density = |t: f64| (-t * t / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let sum: f64 = (0..=steps)
+            .map(|i| {
+                let weight = if i == 0 || i == steps {
+                    1.0
+                } else if i % 2 == 1 {
+                    4.0
+                } else {
+                    2.0
+                };
+                weight * density(-10.0 + i as f64 * step)
+            })
+            .sum();
+        sum * step / 3.0
+    }
+
+    #[test]
+    fn test_ks_test_cdf() {
+        let uniform = |x: f64| x.clamp(0.0, 4.0) / 4.0;
+        let result = ks_test_cdf(&h1(&[30.0, 20.0, 30.0, 20.0]), uniform).unwrap();
+        assert_close(result.statistic, 0.05);
+        assert_close(result.p_value, 0.959_600_445_862_686_4);
+
+        let normal = H1::new(
+            Box::new(FixedWidthAxis::new(-2.0, 2.0, 1.0, 4)),
+            vec![10.0, 40.0, 35.0, 15.0],
+        );
+        let result = ks_test_cdf(&normal, normal_cdf).unwrap();
+        assert!((result.statistic - 0.042_383_613_994_546_96).abs() < 1e-9);
+        assert!((result.p_value - 0.992_751_402_782_558_8).abs() < 1e-9);
+
+        assert!(ks_test_cdf(&h1(&[1.0, 2.0]), |_| 0.5).is_err());
+        assert!(ks_test_cdf(&h1(&[1.0, 2.0]), |x| 1.0 - x).is_err());
+    }
+
+    #[test]
+    fn test_ad_test_cdf() {
+        let uniform = |x: f64| x / 4.0;
+        let result = ad_test_cdf(&h1(&[30.0, 20.0, 30.0, 20.0]), uniform).unwrap();
+        assert_close(result.statistic, 2.0 / 3.0);
+        // Exact asymptotic p-value from mpmath, the approximation is within 2e-5
+        assert!((result.p_value - 0.587_306_646_124_998_6).abs() < 2e-5);
+
+        let normal = H1::new(
+            Box::new(FixedWidthAxis::new(-2.0, 2.0, 1.0, 4)),
+            vec![10.0, 40.0, 35.0, 15.0],
+        );
+        let result = ad_test_cdf(&normal, normal_cdf).unwrap();
+        assert!((result.statistic - 0.226_449_650_146_798_87).abs() < 1e-9);
+        assert!((result.p_value - 0.981_426_700_571_213_5).abs() < 2e-5);
+
+        let same = ad_test_cdf(&h1(&[1.0, 1.0]), |x| x / 2.0).unwrap();
+        assert_eq!(same.statistic, 0.0);
+    }
+}
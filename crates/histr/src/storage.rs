@@ -0,0 +1,318 @@
+//! Storage types for the contents of histogram bins.
+//!
+//! `f64` (the default) and `f32` hold sums of weights, `u64` holds plain
+//! counts, [`Weight`] keeps the sum of weights together with its variance and
+//! [`Mean`] accumulates the mean of samples filled into the bin.
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+/// Content of a histogram bin.
+pub trait Storage: Clone + Debug + Default + PartialEq + Send + Sync + 'static {
+    /// Whether the storage keeps track of the variance of its value.
+    ///
+    /// For other storages, histograms track variances separately once
+    /// weighted values are filled.
+    const HAS_VARIANCE: bool = false;
+
+    /// Value of the bin: count, sum of weights or mean.
+    fn value(&self) -> f64;
+
+    /// Variance of the value, `None` if not tracked by the storage.
+    fn variance(&self) -> Option<f64> {
+        None
+    }
+
+    /// Add the contents of another bin.
+    fn merge(&mut self, other: &Self);
+
+    /// Multiply the value by a factor (and the variance by its square).
+    fn scale(&mut self, factor: f64);
+
+    /// Values of several bins.
+    // A `Vec` so that `f64` contents can be borrowed as they are.
+    #[allow(clippy::ptr_arg)]
+    fn values(contents: &Vec<Self>) -> Cow<'_, Vec<f64>> {
+        Cow::Owned(contents.iter().map(Self::value).collect())
+    }
+}
+
+/// Storage that counts entries with unit weight.
+pub trait Counter: Storage {
+    fn add_count(&mut self, count: u64);
+
+    fn fill(&mut self) {
+        self.add_count(1);
+    }
+}
+
+/// Storage that sums weights of the entries.
+pub trait WeightedStorage: Counter {
+    /// Add a sum of weights, together with the sum of squared weights.
+    fn add_weights(&mut self, sum: f64, sum2: f64);
+
+    fn fill_weighted(&mut self, weight: f64) {
+        self.add_weights(weight, weight * weight);
+    }
+}
+
+impl Storage for f64 {
+    fn value(&self) -> f64 {
+        *self
+    }
+
+    fn merge(&mut self, other: &Self) {
+        *self += other;
+    }
+
+    fn scale(&mut self, factor: f64) {
+        *self *= factor;
+    }
+
+    fn values(contents: &Vec<Self>) -> Cow<'_, Vec<f64>> {
+        Cow::Borrowed(contents)
+    }
+}
+
+impl Counter for f64 {
+    fn add_count(&mut self, count: u64) {
+        *self += count as f64;
+    }
+}
+
+impl WeightedStorage for f64 {
+    fn add_weights(&mut self, sum: f64, _sum2: f64) {
+        *self += sum;
+    }
+}
+
+impl Storage for f32 {
+    fn value(&self) -> f64 {
+        f64::from(*self)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        *self += other;
+    }
+
+    fn scale(&mut self, factor: f64) {
+        *self = (f64::from(*self) * factor) as f32;
+    }
+}
+
+impl Counter for f32 {
+    fn add_count(&mut self, count: u64) {
+        *self += count as f32;
+    }
+}
+
+impl WeightedStorage for f32 {
+    fn add_weights(&mut self, sum: f64, _sum2: f64) {
+        *self = (f64::from(*self) + sum) as f32;
+    }
+}
+
+/// Counts are rounded to the nearest integer when scaled.
+impl Storage for u64 {
+    fn value(&self) -> f64 {
+        *self as f64
+    }
+
+    fn merge(&mut self, other: &Self) {
+        *self += other;
+    }
+
+    fn scale(&mut self, factor: f64) {
+        *self = (*self as f64 * factor).round() as u64;
+    }
+}
+
+impl Counter for u64 {
+    fn add_count(&mut self, count: u64) {
+        *self += count;
+    }
+}
+
+/// Sum of weights with the sum of squared weights as its variance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Weight {
+    pub value: f64,
+    pub variance: f64,
+}
+
+impl Storage for Weight {
+    const HAS_VARIANCE: bool = true;
+
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn variance(&self) -> Option<f64> {
+        Some(self.variance)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.value += other.value;
+        self.variance += other.variance;
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.value *= factor;
+        self.variance *= factor * factor;
+    }
+}
+
+impl Counter for Weight {
+    fn add_count(&mut self, count: u64) {
+        self.value += count as f64;
+        self.variance += count as f64;
+    }
+}
+
+impl WeightedStorage for Weight {
+    fn add_weights(&mut self, sum: f64, sum2: f64) {
+        self.value += sum;
+        self.variance += sum2;
+    }
+}
+
+/// Weighted mean of samples (e.g. a profile histogram).
+///
+/// The samples are accumulated with Welford's algorithm. The value of the bin
+/// is the mean and its variance is the variance of the mean.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Mean {
+    sum_of_weights: f64,
+    sum_of_weights_squared: f64,
+    mean: f64,
+    // Sum of weighted squared deviations from the mean
+    sum_of_deltas_squared: f64,
+}
+
+impl Mean {
+    pub fn fill(&mut self, sample: f64) {
+        self.fill_weighted(sample, 1.0);
+    }
+
+    pub fn fill_weighted(&mut self, sample: f64, weight: f64) {
+        self.sum_of_weights += weight;
+        self.sum_of_weights_squared += weight * weight;
+        let delta = sample - self.mean;
+        self.mean += weight * delta / self.sum_of_weights;
+        self.sum_of_deltas_squared += weight * delta * (sample - self.mean);
+    }
+
+    pub fn sum_of_weights(&self) -> f64 {
+        self.sum_of_weights
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Unbiased variance of the samples, NaN for fewer than two effective entries.
+    pub fn sample_variance(&self) -> f64 {
+        let denominator = self.sum_of_weights - self.sum_of_weights_squared / self.sum_of_weights;
+        if denominator > 0.0 {
+            self.sum_of_deltas_squared / denominator
+        } else {
+            f64::NAN
+        }
+    }
+}
+
+impl Storage for Mean {
+    const HAS_VARIANCE: bool = true;
+
+    fn value(&self) -> f64 {
+        self.mean
+    }
+
+    fn variance(&self) -> Option<f64> {
+        let effective_count = self.sum_of_weights.powi(2) / self.sum_of_weights_squared;
+        Some(self.sample_variance() / effective_count)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        let sum_of_weights = self.sum_of_weights + other.sum_of_weights;
+        if sum_of_weights == 0.0 {
+            return;
+        }
+        let delta = other.mean - self.mean;
+        self.sum_of_deltas_squared += other.sum_of_deltas_squared
+            + delta * delta * self.sum_of_weights * other.sum_of_weights / sum_of_weights;
+        self.mean += delta * other.sum_of_weights / sum_of_weights;
+        self.sum_of_weights = sum_of_weights;
+        self.sum_of_weights_squared += other.sum_of_weights_squared;
+    }
+
+    /// Scales the samples, as if all of them were multiplied by the factor.
+    fn scale(&mut self, factor: f64) {
+        self.mean *= factor;
+        self.sum_of_deltas_squared *= factor * factor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean() {
+        let samples = [1.0, 2.0, 4.0, 7.0];
+        let mut mean = Mean::default();
+        samples.iter().for_each(|sample| mean.fill(*sample));
+
+        assert_eq!(mean.value(), 3.5);
+        assert_eq!(mean.sample_variance(), 7.0);
+        assert_eq!(mean.variance(), Some(1.75));
+    }
+
+    #[test]
+    fn test_mean_merge() {
+        let mut first = Mean::default();
+        let mut second = Mean::default();
+        let mut all = Mean::default();
+        for (i, sample) in [1.0, 2.0, 4.0, 7.0, 3.0].into_iter().enumerate() {
+            let weight = 1.0 + i as f64 * 0.5;
+            all.fill_weighted(sample, weight);
+            if i < 2 {
+                first.fill_weighted(sample, weight);
+            } else {
+                second.fill_weighted(sample, weight);
+            }
+        }
+        first.merge(&second);
+
+        assert!((first.value() - all.value()).abs() < 1e-12);
+        assert!((first.sample_variance() - all.sample_variance()).abs() < 1e-12);
+        first.merge(&Mean::default());
+        assert!((first.value() - all.value()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_weight() {
+        let mut weight = Weight::default();
+        weight.fill();
+        weight.fill_weighted(2.0);
+        weight.scale(2.0);
+
+        assert_eq!(
+            weight,
+            Weight {
+                value: 6.0,
+                variance: 20.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut count = 0u64;
+        count.add_count(3);
+        count.fill();
+        count.scale(0.4);
+
+        assert_eq!(count, 2);
+    }
+}
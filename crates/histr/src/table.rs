@@ -0,0 +1,207 @@
+//! Plain-text tables (CSV / TSV) with one row per bin.
+//!
+//! Columns are `lower`, `upper`, `center`, `content` and optionally `error`
+//! (the square root of the variance). Reading requires `lower`, `upper` and
+//! `content` columns, the other ones are optional.
+
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+use crate::axis::GeneralAxis;
+use crate::h1::H1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TableOptions {
+    pub delimiter: char,
+    pub errors: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            delimiter: ',',
+            errors: false,
+        }
+    }
+}
+
+impl TableOptions {
+    pub fn tsv() -> Self {
+        TableOptions {
+            delimiter: '\t',
+            ..Default::default()
+        }
+    }
+}
+
+pub fn write_table(
+    h1: &H1,
+    writer: &mut impl Write,
+    options: &TableOptions,
+) -> std::io::Result<()> {
+    let d = options.delimiter;
+    write!(writer, "lower{d}upper{d}center{d}content")?;
+    if options.errors {
+        write!(writer, "{d}error")?;
+    }
+    writeln!(writer)?;
+
+    let edges = h1.axis().bin_edges();
+    let variances = h1.variances();
+    for (i, content) in h1.bin_contents().iter().enumerate() {
+        let (lower, upper) = (edges[i], edges[i + 1]);
+        let center = (lower + upper) / 2.0;
+        write!(writer, "{lower}{d}{upper}{d}{center}{d}{content}")?;
+        if options.errors {
+            write!(writer, "{d}{}", variances[i].sqrt())?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Read a table into a histogram with a `GeneralAxis`.
+///
+/// Empty lines and lines starting with `#` are ignored.
+pub fn read_table(reader: impl BufRead, delimiter: char) -> Result<H1<'static>, Box<dyn Error>> {
+    let mut lines = reader.lines().enumerate().filter(|(_, line)| match line {
+        Ok(line) => !line.trim().is_empty() && !line.starts_with('#'),
+        Err(_) => true,
+    });
+
+    let (_, header) = lines.next().ok_or("Empty table.")?;
+    let header: Vec<String> = split_row(&header?, delimiter)
+        .map(|name| name.to_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let lower_column = column("lower").ok_or("Missing column 'lower'.")?;
+    let upper_column = column("upper").ok_or("Missing column 'upper'.")?;
+    let content_column = column("content").ok_or("Missing column 'content'.")?;
+    let error_column = column("error");
+
+    let mut edges: Vec<f64> = Vec::new();
+    let mut contents = Vec::new();
+    let mut variances = Vec::new();
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let line = line?;
+        let row: Vec<&str> = split_row(&line, delimiter).collect();
+        if row.len() != header.len() {
+            return Err(format!("Wrong number of columns on line {line_number}.").into());
+        }
+        let parse = |column: usize| -> Result<f64, Box<dyn Error>> {
+            row[column]
+                .parse()
+                .map_err(|_| format!("Invalid number on line {line_number}.").into())
+        };
+        let (lower, upper) = (parse(lower_column)?, parse(upper_column)?);
+        if lower.is_nan() || upper.is_nan() || lower >= upper {
+            return Err(format!("Edges are not increasing on line {line_number}.").into());
+        }
+        match edges.last() {
+            None => edges.push(lower),
+            Some(&previous) if previous != lower => {
+                return Err(format!("Bins are not contiguous on line {line_number}.").into());
+            }
+            _ => {}
+        }
+        edges.push(upper);
+        contents.push(parse(content_column)?);
+        if let Some(error_column) = error_column {
+            variances.push(parse(error_column)?.powi(2));
+        }
+    }
+    if contents.is_empty() {
+        return Err("The table has no bins.".into());
+    }
+
+    let mut h1 = H1::new(Box::new(GeneralAxis::new(edges)), contents);
+    if error_column.is_some() {
+        h1.set_variances(Some(variances))?;
+    }
+    Ok(h1)
+}
+
+fn split_row(line: &str, delimiter: char) -> impl Iterator<Item = &str> {
+    line.split(delimiter)
+        .map(|field| field.trim().trim_matches('"'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::FixedWidthAxis;
+    use std::error::Error;
+
+    fn get_h1() -> H1<'static> {
+        H1::new(
+            Box::new(FixedWidthAxis::create_from_min_and_bins(0.0, 0.5, 2)),
+            vec![4.0, 9.0],
+        )
+    }
+
+    fn write_to_string(h1: &H1, options: &TableOptions) -> String {
+        let mut buffer = Vec::new();
+        write_table(h1, &mut buffer, options).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(
+            write_to_string(&get_h1(), &TableOptions::default()),
+            "lower,upper,center,content\n0,0.5,0.25,4\n0.5,1,0.75,9\n"
+        );
+        let options = TableOptions {
+            errors: true,
+            ..TableOptions::tsv()
+        };
+        assert_eq!(
+            write_to_string(&get_h1(), &options),
+            "lower\tupper\tcenter\tcontent\terror\n0\t0.5\t0.25\t4\t2\n0.5\t1\t0.75\t9\t3\n"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), Box<dyn Error>> {
+        let mut h1 = get_h1();
+        h1.fill_weighted(0.2, 0.5);
+        let options = TableOptions {
+            errors: true,
+            ..Default::default()
+        };
+        let table = write_to_string(&h1, &options);
+        let restored = read_table(table.as_bytes(), ',')?;
+
+        assert_eq!(restored.axis().bin_edges(), h1.axis().bin_edges());
+        assert_eq!(restored.bin_contents(), h1.bin_contents());
+        assert_eq!(restored.variances(), h1.variances());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_minimal() -> Result<(), Box<dyn Error>> {
+        let table = "# comment\nContent,Lower,Upper\n\n1,0,1\n2,1,3\n";
+        let h1 = read_table(table.as_bytes(), ',')?;
+
+        assert_eq!(*h1.axis().bin_edges(), vec![0.0, 1.0, 3.0]);
+        assert_eq!(h1.bin_contents(), &vec![1.0, 2.0]);
+        assert!(!h1.has_variances());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        let read = |table: &str| read_table(table.as_bytes(), ',');
+
+        assert!(read("").is_err());
+        assert!(read("lower,upper\n0,1\n").is_err());
+        assert!(read("lower,upper,content\n").is_err());
+        assert!(read("lower,upper,content\n0,1,x\n").is_err());
+        assert!(read("lower,upper,content\n0,1\n").is_err());
+        // Not contiguous
+        assert!(read("lower,upper,content\n0,1,1\n2,3,1\n").is_err());
+        // Not increasing
+        assert!(read("lower,upper,content\n1,0,1\n").is_err());
+    }
+}
@@ -0,0 +1,367 @@
+//! Conversion from and to the UHI serialization schema.
+//!
+//! This is the JSON representation used by boost-histogram / hist
+//! (see https://uhi.readthedocs.io/en/latest/serialization.html).
+//! Regular axes map to `FixedWidthAxis` and variable axes to `GeneralAxis`.
+//! Histograms are written with flow bins and a double or weighted storage.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::axis::{Axis, FixedWidthAxis, GeneralAxis};
+use crate::h1::{Flow, H1};
+
+pub const UHI_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UhiHistogram {
+    pub uhi_schema: u32,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub writer_info: BTreeMap<String, Value>,
+    pub axes: Vec<UhiAxis>,
+    pub storage: UhiStorage,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UhiAxis {
+    Regular {
+        lower: f64,
+        upper: f64,
+        bins: usize,
+        underflow: bool,
+        overflow: bool,
+        circular: bool,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        metadata: BTreeMap<String, Value>,
+    },
+    Variable {
+        edges: Vec<f64>,
+        underflow: bool,
+        overflow: bool,
+        circular: bool,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        metadata: BTreeMap<String, Value>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UhiStorage {
+    Int { values: Vec<f64> },
+    Double { values: Vec<f64> },
+    Weighted { values: Vec<f64>, variances: Vec<f64> },
+}
+
+impl UhiAxis {
+    fn flow(&self) -> (bool, bool) {
+        match self {
+            UhiAxis::Regular {
+                underflow,
+                overflow,
+                ..
+            }
+            | UhiAxis::Variable {
+                underflow,
+                overflow,
+                ..
+            } => (*underflow, *overflow),
+        }
+    }
+}
+
+impl From<&dyn Axis> for UhiAxis {
+    fn from(axis: &dyn Axis) -> Self {
+        match axis.as_any().downcast_ref::<FixedWidthAxis>() {
+            Some(axis) => UhiAxis::Regular {
+                lower: axis.min_edge(),
+                upper: axis.max_edge(),
+                bins: axis.len(),
+                underflow: true,
+                overflow: true,
+                circular: false,
+                metadata: BTreeMap::new(),
+            },
+            None => UhiAxis::Variable {
+                edges: axis.bin_edges().to_vec(),
+                underflow: true,
+                overflow: true,
+                circular: false,
+                metadata: BTreeMap::new(),
+            },
+        }
+    }
+}
+
+impl TryFrom<&UhiAxis> for Box<dyn Axis> {
+    type Error = &'static str;
+
+    fn try_from(axis: &UhiAxis) -> Result<Self, &'static str> {
+        match axis {
+            UhiAxis::Regular { circular: true, .. } | UhiAxis::Variable { circular: true, .. } => {
+                Err("Circular axes are not supported.")
+            }
+            UhiAxis::Regular {
+                lower, upper, bins, ..
+            } => {
+                // Finite first, as comparisons with NaN are always false
+                if *bins == 0 || !lower.is_finite() || !upper.is_finite() || upper <= lower {
+                    return Err("Invalid regular axis.");
+                }
+                let bin_width = (upper - lower) / *bins as f64;
+                Ok(Box::new(FixedWidthAxis::new(
+                    *lower, *upper, bin_width, *bins,
+                )))
+            }
+            UhiAxis::Variable { edges, .. } => {
+                if edges.len() < 2
+                    || edges.iter().any(|edge| !edge.is_finite())
+                    || edges.windows(2).any(|pair| pair[0] >= pair[1])
+                {
+                    return Err("Edges of a variable axis must be increasing.");
+                }
+                Ok(Box::new(GeneralAxis::new(edges.clone())))
+            }
+        }
+    }
+}
+
+pub fn to_uhi(h1: &H1) -> UhiHistogram {
+    let with_flow = |values: &[f64], underflow: f64, overflow: f64| {
+        let mut result = Vec::with_capacity(values.len() + 2);
+        result.push(underflow);
+        result.extend_from_slice(values);
+        result.push(overflow);
+        result
+    };
+    let (underflow, overflow) = (h1.underflow(), h1.overflow());
+    let values = with_flow(h1.bin_contents(), underflow.value, overflow.value);
+    let storage = if h1.has_variances() {
+        UhiStorage::Weighted {
+            values,
+            variances: with_flow(&h1.variances(), underflow.variance, overflow.variance),
+        }
+    } else {
+        UhiStorage::Double { values }
+    };
+
+    let mut writer_info = BTreeMap::new();
+    writer_info.insert(
+        "histr".to_string(),
+        serde_json::json!({ "version": env!("CARGO_PKG_VERSION") }),
+    );
+
+    UhiHistogram {
+        uhi_schema: UHI_SCHEMA_VERSION,
+        writer_info,
+        axes: vec![UhiAxis::from(h1.axis())],
+        storage,
+        metadata: h1
+            .metadata()
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+            .collect(),
+    }
+}
+
+pub fn from_uhi(histogram: &UhiHistogram) -> Result<H1<'static>, Box<dyn Error>> {
+    if histogram.uhi_schema != UHI_SCHEMA_VERSION {
+        return Err("Unsupported UHI schema version.".into());
+    }
+    let [uhi_axis] = histogram.axes.as_slice() else {
+        return Err("Only one-dimensional histograms are supported.".into());
+    };
+    let axis: Box<dyn Axis> = uhi_axis.try_into()?;
+    let (has_underflow, has_overflow) = uhi_axis.flow();
+
+    // Values include the flow bins that the axis declares
+    let split = |values: &[f64]| -> Result<(Vec<f64>, f64, f64), &'static str> {
+        let n = axis.len();
+        let expected = n + has_underflow as usize + has_overflow as usize;
+        if values.len() != expected {
+            return Err("Storage and axis lengths must match.");
+        }
+        let start = has_underflow as usize;
+        let underflow = if has_underflow { values[0] } else { 0.0 };
+        let overflow = if has_overflow { values[expected - 1] } else { 0.0 };
+        Ok((values[start..start + n].to_vec(), underflow, overflow))
+    };
+
+    let mut h1 = match &histogram.storage {
+        UhiStorage::Int { values } | UhiStorage::Double { values } => {
+            let (contents, underflow, overflow) = split(values)?;
+            let mut h1 = H1::new(axis.clone_box(), contents);
+            h1.set_underflow(Flow {
+                value: underflow,
+                variance: underflow,
+            });
+            h1.set_overflow(Flow {
+                value: overflow,
+                variance: overflow,
+            });
+            h1
+        }
+        UhiStorage::Weighted { values, variances } => {
+            let (contents, underflow, overflow) = split(values)?;
+            let (variances, underflow_variance, overflow_variance) = split(variances)?;
+            let mut h1 = H1::new(axis.clone_box(), contents);
+            h1.set_variances(Some(variances))?;
+            h1.set_underflow(Flow {
+                value: underflow,
+                variance: underflow_variance,
+            });
+            h1.set_overflow(Flow {
+                value: overflow,
+                variance: overflow_variance,
+            });
+            h1
+        }
+    };
+    for (key, value) in &histogram.metadata {
+        let value = match value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        h1.metadata_mut().insert(key.clone(), value);
+    }
+    Ok(h1)
+}
+
+pub fn to_uhi_json(h1: &H1) -> String {
+    serde_json::to_string(&to_uhi(h1)).expect("UHI histograms are always serializable.")
+}
+
+pub fn from_uhi_json(json: &str) -> Result<H1<'static>, Box<dyn Error>> {
+    from_uhi(&serde_json::from_str(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_regular_roundtrip() -> Result<(), Box<dyn Error>> {
+        let mut h1 = H1::new(
+            Box::new(FixedWidthAxis::create_from_min_and_bins(0.0, 1.0, 3)),
+            vec![0.0; 3],
+        );
+        h1.fill_many(&[-1.0, 0.5, 1.5, 2.5, 2.7, 4.0]);
+        h1.metadata_mut().insert("name".into(), "x".into());
+
+        let restored = from_uhi_json(&to_uhi_json(&h1))?;
+        assert!(restored.axis().as_any().is::<FixedWidthAxis>());
+        assert_eq!(restored.axis().bin_edges(), h1.axis().bin_edges());
+        assert_eq!(restored.bin_contents(), &vec![1.0, 1.0, 2.0]);
+        assert_eq!(restored.underflow().value, 1.0);
+        assert_eq!(restored.overflow().value, 1.0);
+        assert_eq!(restored.metadata(), h1.metadata());
+        assert!(!restored.has_variances());
+        Ok(())
+    }
+
+    #[test]
+    fn test_weighted_storage() -> Result<(), Box<dyn Error>> {
+        let mut h1 = H1::new(Box::new(GeneralAxis::new(vec![0., 1., 5.])), vec![0.0; 2]);
+        h1.fill_weighted(2.0, 3.0);
+
+        let uhi = to_uhi(&h1);
+        assert_eq!(
+            uhi.storage,
+            UhiStorage::Weighted {
+                values: vec![0.0, 0.0, 3.0, 0.0],
+                variances: vec![0.0, 0.0, 9.0, 0.0],
+            }
+        );
+
+        let restored = from_uhi(&uhi)?;
+        assert!(restored.axis().as_any().is::<GeneralAxis>());
+        assert_eq!(*restored.variances(), vec![0.0, 9.0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reads_boost_histogram_output() -> Result<(), Box<dyn Error>> {
+        let json = r#"{
+            "uhi_schema": 1,
+            "writer_info": {"boost-histogram": {"version": "1.5.0"}},
+            "axes": [{
+                "type": "regular", "lower": 0.0, "upper": 2.0, "bins": 2,
+                "underflow": true, "overflow": false, "circular": false,
+                "metadata": {"name": "x"}
+            }],
+            "storage": {"type": "int", "values": [4, 1, 2]},
+            "metadata": {"count": 3}
+        }"#;
+        let h1 = from_uhi_json(json)?;
+        assert_eq!(h1.bin_contents(), &vec![1.0, 2.0]);
+        assert_eq!(h1.underflow().value, 4.0);
+        assert_eq!(h1.metadata()["count"], "3");
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_unsupported() {
+        let two_axes = r#"{"uhi_schema": 1, "axes": [
+            {"type": "regular", "lower": 0, "upper": 1, "bins": 1, "underflow": false, "overflow": false, "circular": false},
+            {"type": "regular", "lower": 0, "upper": 1, "bins": 1, "underflow": false, "overflow": false, "circular": false}
+        ], "storage": {"type": "double", "values": [1.0]}}"#;
+        assert!(from_uhi_json(two_axes).is_err());
+
+        let mean = r#"{"uhi_schema": 1, "axes": [
+            {"type": "regular", "lower": 0, "upper": 1, "bins": 1, "underflow": false, "overflow": false, "circular": false}
+        ], "storage": {"type": "mean", "counts": [1.0], "values": [1.0], "variances": [0.0]}}"#;
+        assert!(from_uhi_json(mean).is_err());
+    }
+
+    fn regular(lower: &str, upper: &str) -> String {
+        format!(
+            r#"{{"uhi_schema": 1, "axes": [
+                {{"type": "regular", "lower": {lower}, "upper": {upper}, "bins": 1, "underflow": false, "overflow": false, "circular": false}}
+            ], "storage": {{"type": "double", "values": [1.0]}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_rejects_invalid_axes() {
+        assert!(from_uhi_json(&regular("0", "1")).is_ok());
+        assert!(from_uhi_json(&regular("1", "0")).is_err());
+        // Not finite bounds and edges, as parsed from JSON or given from Python
+        for (lower, upper) in [(f64::NAN, 1.0), (0.0, f64::NAN), (0.0, f64::INFINITY), (f64::NEG_INFINITY, 0.0)] {
+            let axis = UhiAxis::Regular {
+                lower,
+                upper,
+                bins: 1,
+                underflow: false,
+                overflow: false,
+                circular: false,
+                metadata: BTreeMap::new(),
+            };
+            assert!(<Box<dyn Axis>>::try_from(&axis).is_err(), "{lower}, {upper}");
+        }
+        for edges in [vec![0.0, f64::NAN], vec![f64::NAN, 1.0], vec![0.0, 1.0, f64::INFINITY]] {
+            let axis = UhiAxis::Variable {
+                edges,
+                underflow: false,
+                overflow: false,
+                circular: false,
+                metadata: BTreeMap::new(),
+            };
+            assert!(<Box<dyn Axis>>::try_from(&axis).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_missing_flow_values() {
+        let json = r#"{"uhi_schema": 1, "axes": [
+            {"type": "regular", "lower": 0, "upper": 2, "bins": 2, "underflow": true, "overflow": true, "circular": false}
+        ], "storage": {"type": "double", "values": [1.0, 2.0]}}"#;
+        assert!(from_uhi_json(json).is_err());
+        assert!(from_uhi_json(&json.replace("[1.0, 2.0]", "[0.0, 1.0, 2.0, 3.0]")).is_ok());
+    }
+}
@@ -1,24 +1,362 @@
+import copy
+import pickle
+from pathlib import Path
+
 import numpy as np
 import pytest
-from histr import h1
+from histr import (
+    H1,
+    Bin,
+    FixedWidthAxis,
+    FixedWidthBins,
+    GeneralAxis,
+    PrettyBins,
+    RuleBins,
+    StandardBins,
+    bhattacharyya_distance,
+    h1,
+    hellinger_distance,
+    intersection,
+    js_divergence,
+    kl_divergence,
+    total_variation,
+    wasserstein_distance,
+)
 
 class TestH1:
     def test_works_no_args(self):
         h = h1([1, 2, 2.04])
         assert h.bin_edges == pytest.approx([1.0, 1.1, 1.2, 1.3, 1.4, 1.5, 1.6, 1.7, 1.8, 1.9, 2.0, 2.1])
-        assert h.bin_contents == [1.0] + [0.0] * 9 + [2.0]
+        assert h.bin_contents.tolist() == [1.0] + [0.0] * 9 + [2.0]
 
     def test_works_bin_width(self):
         h = h1([1, 2, 2.04], bin_width=0.4)
         assert h.bin_edges == pytest.approx([0.8, 1.2, 1.6, 2.0, 2.4])
-        assert h.bin_contents == [1.0, 0.0, 0.0, 2.0]
+        assert h.bin_contents.tolist() == [1.0, 0.0, 0.0, 2.0]
 
     def test_works_bin_edges(self):
         h = h1([1, 2, 2.04], bin_edges=[1.0, 1.2, 1.4, 1.6, 1.8, 2.0, 2.2])
         assert h.bin_edges == pytest.approx([1.0, 1.2, 1.4, 1.6, 1.8, 2.0, 2.2])
-        assert h.bin_contents == [1.0] + [0.0] * 4 + [2.0]
+        assert h.bin_contents.tolist() == [1.0] + [0.0] * 4 + [2.0]
+
+
+class TestNumpy:
+    @pytest.mark.parametrize("dtype", ["float64", "float32", "int8", "int16", "int32", "int64", "uint8", "uint16", "uint32", "uint64"])
+    def test_accepts_numeric_dtypes(self, dtype):
+        data = np.array([1, 2, 2, 3], dtype=dtype)
+        h = h1(data, bin_edges=np.array([0.0, 1.5, 3.0]))
+        np.testing.assert_array_equal(h.bin_contents, [1.0, 3.0])
+
+        h.fill_many(data, weights=np.ones(4, dtype="float32"))
+        np.testing.assert_array_equal(h.bin_contents, [2.0, 6.0])
+
+    def test_accepts_non_contiguous(self):
+        data = np.array([1.0, 100.0, 2.0, 100.0])[::2]
+        h = h1(data, bin_edges=[0.0, 1.5, 3.0])
+        np.testing.assert_array_equal(h.bin_contents, [1.0, 1.0])
+
+    def test_returns_arrays(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        assert isinstance(h.bin_contents, np.ndarray)
+        assert isinstance(h.bin_edges, np.ndarray)
+        assert h.bin_contents.dtype == np.float64
+
+    def test_array_protocol(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        np.testing.assert_array_equal(np.asarray(h), h.bin_contents)
+        assert np.asarray(h, dtype="float32").dtype == np.float32
+
+    def test_invalid_input(self):
+        with pytest.raises(TypeError):
+            h1("abc")
+        with pytest.raises(TypeError):
+            h1(np.zeros((2, 2)))
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        with pytest.raises(TypeError):
+            h.fill_many([1.0, "x"])
+        with pytest.raises(ValueError):
+            h.fill_many([1.0, 2.0], weights=[1.0])
+
+
+class TestSaveLoad:
+    def test_roundtrip(self, tmp_path):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        h.fill(7.0, weight=2.0)
+        path = tmp_path / "h.hst"
+        h.save(path)
+
+        restored = H1.load(path)
+        assert restored.bin_edges == pytest.approx(h.bin_edges)
+        np.testing.assert_array_equal(restored.bin_contents, h.bin_contents)
+
+    def test_missing_file(self, tmp_path):
+        with pytest.raises(IOError):
+            H1.load(tmp_path / "missing.hst")
+
+
+class TestUhi:
+    def test_roundtrip(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        data = h.to_uhi()
+        assert data["uhi_schema"] == 1
+        assert data["axes"][0]["type"] == "regular"
+        assert data["storage"]["type"] == "double"
+
+        restored = H1.from_uhi(data)
+        assert restored.bin_edges == pytest.approx(h.bin_edges)
+        np.testing.assert_array_equal(restored.bin_contents, h.bin_contents)
+
+
+class TestCsv:
+    def test_roundtrip(self, tmp_path):
+        h = h1([1, 2, 2.04], bin_edges=[1.0, 1.5, 2.0, 3.0])
+        path = tmp_path / "h.csv"
+        h.to_csv(path, errors=True)
+        assert path.read_text().splitlines()[0] == "lower,upper,center,content,error"
+
+        restored = H1.from_csv(path)
+        assert restored.bin_edges == pytest.approx(h.bin_edges)
+        np.testing.assert_array_equal(restored.bin_contents, h.bin_contents)
+
+    def test_invalid_table(self, tmp_path):
+        path = tmp_path / "h.tsv"
+        path.write_text("lower\tupper\tcontent\n0\t1\t1\n2\t3\t1\n")
+        with pytest.raises(ValueError):
+            H1.from_csv(path, delimiter="\t")
+
+
+class TestRoot:
+    # Written by histr::root, kept up to date by a test in the Rust crate
+    path = Path(__file__).parents[1] / "crates" / "histr" / "fixtures" / "histr_written.root"
+
+    def test_read_by_uproot(self):
+        uproot = pytest.importorskip("uproot")
+        with uproot.open(self.path) as file:
+            hist = file["hist"]
+            assert hist.classname == "TH1D"
+            assert hist.member("fTitle") == "Energy"
+            np.testing.assert_array_equal(hist.axis().edges(), [0.0, 0.5, 1.0, 1.5, 2.0])
+            np.testing.assert_array_equal(hist.values(flow=True), [1.0, 1.0, 2.0, 2.0, 1.0, 1.0])
+            np.testing.assert_array_equal(hist.variances(flow=True), [1.0, 1.0, 2.0, 4.0, 1.0, 1.0])
+
+
+class TestPlottable:
+    def test_protocol(self):
+        plottable = pytest.importorskip("uhi.typing.plottable")
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        assert isinstance(h, plottable.PlottableHistogram)
+        assert isinstance(h.axes[0], plottable.PlottableAxis)
+
+    def test_values(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        assert h.kind == "COUNT"
+        np.testing.assert_array_equal(h.values(), [1.0, 0.0, 0.0, 2.0])
+        np.testing.assert_array_equal(h.variances(), [1.0, 0.0, 0.0, 2.0])
+        np.testing.assert_array_equal(h.counts(), [1.0, 0.0, 0.0, 2.0])
+
+    def test_weighted_counts(self):
+        h = h1([1.0], bin_edges=[0.0, 2.0, 4.0])
+        h.fill(3.0, weight=2.0)
+        h.fill(3.0, weight=2.0)
+        np.testing.assert_array_equal(h.values(), [1.0, 4.0])
+        np.testing.assert_array_equal(h.variances(), [1.0, 8.0])
+        np.testing.assert_array_equal(h.counts(), [1.0, 2.0])
 
+    def test_axes(self):
+        h = h1([1, 2, 2.04], bin_edges=[1.0, 1.5, 2.5])
+        (axis,) = h.axes
+        assert len(axis) == 2
+        assert axis[0] == (1.0, 1.5)
+        assert axis[-1] == (1.5, 2.5)
+        with pytest.raises(IndexError):
+            axis[2]
+        np.testing.assert_array_equal(axis.edges, [1.0, 1.5, 2.5])
+        np.testing.assert_array_equal(axis.centers, [1.25, 2.0])
+        np.testing.assert_array_equal(axis.widths, [0.5, 1.0])
+        assert not axis.traits.circular
+        assert not axis.traits.discrete
+        assert axis == h.axes[0]
+
+
+class TestAxes:
+    def test_general_axis(self):
+        axis = GeneralAxis([0.0, 1.0, 3.0])
+        assert len(axis) == 2
+        assert axis.find_bin(2.0) == 1
+        assert axis.find_bin(4.0) is None
+        with pytest.raises(ValueError):
+            GeneralAxis([0.0, 2.0, 1.0])
+
+    def test_fixed_width_axis(self):
+        axis = FixedWidthAxis(0.0, 0.5, 4)
+        assert axis.bin_width == 0.5
+        assert axis.max_edge == 2.0
+        assert axis == FixedWidthAxis.from_range(0.0, 2.0, 0.5)
+        assert isinstance(h1([1, 2], bin_width=0.5).axis, FixedWidthAxis)
+
+    def test_h1_from_axis(self):
+        h = H1(GeneralAxis([0.0, 1.0, 3.0]), [1.0, 2.0])
+        assert h.get_bin(1) == Bin(2.0, 1.0, 3.0)
+        assert h.get_bin(1).width == 2.0
+        with pytest.raises(IndexError):
+            h.get_bin(2)
+        with pytest.raises(ValueError):
+            H1(GeneralAxis([0.0, 1.0]), [1.0, 2.0])
+
+
+class TestBins:
+    def test_int(self):
+        h = h1([0, 1, 2, 4], bins=4)
+        np.testing.assert_array_equal(h.bin_edges, [0.0, 1.0, 2.0, 3.0, 4.0])
+        np.testing.assert_array_equal(h.bin_contents, [1.0, 1.0, 1.0, 1.0])
+
+    def test_edges(self):
+        h = h1([0.5, 1.5, 1.6], [0.0, 1.0, 2.0])
+        np.testing.assert_array_equal(h.bin_contents, [1.0, 2.0])
+
+    def test_rule(self):
+        h = h1(np.arange(100.0), bins="sqrt")
+        assert len(h) == 10
+        with pytest.raises(ValueError):
+            h1([1.0, 2.0], bins="unknown")
+
+    @pytest.mark.parametrize(
+        "algorithm",
+        [StandardBins(5), FixedWidthBins(0.5), PrettyBins(4), RuleBins("sturges")],
+    )
+    def test_algorithm(self, algorithm):
+        data = [0.0, 0.3, 1.1, 2.0]
+        axis = algorithm.find_axis(data)
+        assert h1(data, bins=algorithm).axis == axis
+
+    def test_range(self):
+        h = h1([-1.0, 0.5, 1.5, 5.0], bins=2, range=(0.0, 2.0))
+        np.testing.assert_array_equal(h.bin_edges, [0.0, 1.0, 2.0])
+        np.testing.assert_array_equal(h.bin_contents, [1.0, 1.0])
+        assert StandardBins(4).find_axis([], range=(0.0, 2.0)) == FixedWidthAxis(0.0, 0.5, 4)
+
+    def test_range_off_grid(self):
+        # The explicit range is kept, not widened to multiples of the width
+        h = h1([-1.0, 0.0, 0.9], bin_width=0.5, range=(-0.25, 1.0))
+        np.testing.assert_array_equal(h.bin_edges, [-0.25, 0.25, 0.75, 1.0])
+        np.testing.assert_array_equal(h.bin_contents, [1.0, 0.0, 1.0])
+
+        # Beyond the last edge but within a whole last bin
+        h.fill_many([1.1])
+        np.testing.assert_array_equal(h.bin_contents, [1.0, 0.0, 1.0])
+        single = h1([-1.0, 0.0, 0.9], bin_width=0.5, range=(-0.25, 1.0))
+        single.fill(1.1)
+        assert single == h
+
+    def test_conflicting_arguments(self):
+        with pytest.raises(ValueError):
+            h1([1.0], bins=2, bin_width=0.5)
+        with pytest.raises(ValueError):
+            h1([1.0], [0.0, 2.0], range=(0.0, 1.0))
+
+
+class TestPickle:
+    def test_pickle(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        h.fill(10.0, weight=2.0)
+        restored = pickle.loads(pickle.dumps(h))
+        assert restored == h
+        np.testing.assert_array_equal(restored.variances(), h.variances())
+
+    def test_bytes(self):
+        h = h1([1, 2, 2.04], bin_edges=[1.0, 1.5, 2.5])
+        assert H1.from_bytes(h.to_bytes()) == h
+        with pytest.raises(ValueError):
+            H1.from_bytes(b"not a histogram")
+
+    def test_copy(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        for copied in [h.copy(), copy.copy(h), copy.deepcopy(h)]:
+            assert copied == h
+            copied.fill(1.0)
+            assert copied != h
+
+    def test_approx_eq(self):
+        h = H1(GeneralAxis([0.0, 1.0, 2.0]), [1.0, 2.0])
+        other = H1(GeneralAxis([0.0, 1.0, 2.0]), [1.0, 2.0 + 1e-12])
+        assert h != other
+        assert h.approx_eq(other)
+        assert not h.approx_eq(other, rel_tol=0.0)
+        assert h.approx_eq(other, rel_tol=0.0, abs_tol=1e-9)
+        assert h != "histogram"
+
+
+class TestParallel:
+    def test_fill_many_par(self):
+        values = np.linspace(-1.0, 9.0, 100_001)
+        serial = h1([], bin_edges=[0.0, 1.0, 2.0, 4.0, 8.0])
+        serial.fill_many(values)
+        parallel = h1([], bin_edges=[0.0, 1.0, 2.0, 4.0, 8.0])
+        parallel.fill_many_par(values)
+        assert parallel == serial
+
+    @pytest.mark.parametrize("dtype", ["int32", "uint8", "float32"])
+    def test_fill_many_par_dtypes(self, dtype):
+        values = np.arange(100_000) % 10
+        serial = h1([], bin_edges=[0.0, 1.0, 2.0, 4.0, 8.0])
+        serial.fill_many(values.astype(dtype))
+        parallel = h1([], bin_edges=[0.0, 1.0, 2.0, 4.0, 8.0])
+        parallel.fill_many_par(values.astype(dtype))
+        assert parallel == serial
+
+    def test_fill_weighted_many_par(self):
+        values = np.linspace(-1.0, 9.0, 100_001)
+        weights = np.full_like(values, 0.5)
+        serial = h1([], bin_edges=[0.0, 1.0, 2.0, 4.0, 8.0])
+        serial.fill_many(values, weights=weights)
+        parallel = h1([], bin_edges=[0.0, 1.0, 2.0, 4.0, 8.0])
+        parallel.fill_many_par(values, weights=weights)
+        assert parallel.approx_eq(serial)
+        with pytest.raises(ValueError):
+            parallel.fill_many_par(values, weights=weights[1:])
+
+
+class TestText:
+    def test_to_text(self):
+        h = H1(GeneralAxis([0.0, 0.5, 1.0, 2.0]), [4.0, 1.0, 0.0])
+        assert h.to_text(width=8, color=False) == (
+            "[0.0, 0.5) ████████ 4\n"
+            "[0.5, 1.0) ██       1\n"
+            "[1.0, 2.0]          0\n"
+        )
+        assert "#" in h.to_text(ascii=True, vertical=True, color=False)
+
+    def test_str(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        assert len(str(h).splitlines()) == len(h)
+
+    def test_repr_html(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        assert h._repr_html_().startswith("<table")
+
+    def test_to_svg(self):
+        h = h1([1, 2, 2.04], bin_width=0.4)
+        svg = h.to_svg(style="errorbar", title="a < b", logy=True)
+        assert svg.startswith("<svg")
+        assert "a &lt; b" in svg
+        with pytest.raises(ValueError):
+            h.to_svg(style="pie")
 
 
+class TestDistance:
+    def test_distances(self):
+        p = H1(FixedWidthAxis(0.0, 1.0, 4), [1.0, 2.0, 3.0, 4.0])
+        q = H1(FixedWidthAxis(0.0, 1.0, 4), [40.0, 30.0, 20.0, 10.0])
+        assert kl_divergence(p, q) == pytest.approx(0.4564348191467836)
+        assert js_divergence(p, q) == pytest.approx(0.10644013528622315)
+        assert hellinger_distance(p, q) == pytest.approx(0.3318162917087773)
+        assert bhattacharyya_distance(p, q) == pytest.approx(0.11664848737353888)
+        assert wasserstein_distance(p, q) == pytest.approx(1.0)
+        assert intersection(p, q) == pytest.approx(0.6)
+        assert total_variation(p, q) == pytest.approx(0.4)
 
-        
\ No newline at end of file
+    def test_different_axes(self):
+        p = H1(FixedWidthAxis(0.0, 1.0, 4), [1.0, 2.0, 3.0, 4.0])
+        q = H1(FixedWidthAxis(0.0, 1.0, 2), [1.0, 2.0])
+        with pytest.raises(ValueError):
+            kl_divergence(p, q)